@@ -0,0 +1,98 @@
+use std::alloc::{self, Layout};
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+
+/// Alignment, in bytes, of the storage of `Vector` and `Matrix`: a cache line, which also satisfies the
+/// 32-byte alignment of AVX loads and stores.
+pub const ALIGNMENT: usize = 64;
+
+/// fixed-length heap buffer whose first element is aligned to `ALIGNMENT` bytes
+pub(crate) struct AlignedBuf<T> {
+    ptr: NonNull<T>,
+    len: usize,
+    marker: PhantomData<T>,
+}
+
+// SAFETY: the buffer owns its elements like a `Box<[T]>` does.
+unsafe impl<T: Send> Send for AlignedBuf<T> {}
+unsafe impl<T: Sync> Sync for AlignedBuf<T> {}
+
+impl<T: Copy> AlignedBuf<T> {
+    pub fn from_elem(value: T, len: usize) -> Self {
+        let layout = match Self::layout(len) {
+            Some(layout) => layout,
+            None => return AlignedBuf { ptr: NonNull::dangling(), len, marker: PhantomData },
+        };
+        // SAFETY: `layout` has a nonzero size.
+        let raw = unsafe { alloc::alloc(layout) } as *mut T;
+        let ptr = match NonNull::new(raw) {
+            Some(ptr) => ptr,
+            None => alloc::handle_alloc_error(layout),
+        };
+        for i in 0 .. len {
+            // SAFETY: `i < len` and the allocation holds `len` elements.
+            unsafe { ptr.as_ptr().add(i).write(value) };
+        }
+        AlignedBuf { ptr, len, marker: PhantomData }
+    }
+
+    pub fn from_slice(values: &[T]) -> Self {
+        let mut buf = match values.first() {
+            Some(&first) => AlignedBuf::from_elem(first, values.len()),
+            None => return AlignedBuf { ptr: NonNull::dangling(), len: 0, marker: PhantomData },
+        };
+        buf.copy_from_slice(values);
+        buf
+    }
+}
+
+impl<T> AlignedBuf<T> {
+    /// layout of `len` elements, `None` when nothing needs to be allocated
+    fn layout(len: usize) -> Option<Layout> {
+        let size = mem::size_of::<T>().checked_mul(len).expect("capacity overflow");
+        if size == 0 {
+            return None;
+        }
+        Some(Layout::from_size_align(size, ALIGNMENT.max(mem::align_of::<T>())).expect("capacity overflow"))
+    }
+}
+
+impl<T> Drop for AlignedBuf<T> {
+    fn drop(&mut self) {
+        if let Some(layout) = Self::layout(self.len) {
+            // SAFETY: the buffer was allocated in `from_elem` with this very layout, and `T: Copy` elements
+            // need no drop.
+            unsafe { alloc::dealloc(self.ptr.as_ptr() as *mut u8, layout) };
+        }
+    }
+}
+
+impl<T> Deref for AlignedBuf<T> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        // SAFETY: `ptr` is valid for `len` initialized elements, or dangling with `len * size_of::<T>() == 0`.
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T> DerefMut for AlignedBuf<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        // SAFETY: see `deref`, and `&mut self` guarantees exclusive access.
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T: Copy> Clone for AlignedBuf<T> {
+    fn clone(&self) -> Self {
+        AlignedBuf::from_slice(self)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for AlignedBuf<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
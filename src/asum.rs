@@ -1,52 +1,111 @@
-use std::ops::{AddAssign};
-
-trait ASumValue {
-    fn a_sum_value(x: Self) -> Self;
-}
-impl ASumValue for f32 {
-    fn a_sum_value(x: Self) -> Self {
-        f32::abs(x)
-    }
-}
-impl ASumValue for f64 {
-    fn a_sum_value(x: Self) -> Self {
-        f64::abs(x)
-    }
-}
-
-
-fn asum<T>(n: isize, x: &[T], incx: isize) -> T
-where T: Default + AddAssign + Copy + ASumValue,
-{
-    if n <= 0 || incx <= 0 {
-        return <T as Default>::default();
-    }
-
-    let n_usize: usize = n as usize;
-    let incx_usize: usize = incx as usize;
-    if x.len() < 1 + (n_usize - 1) * incx_usize {
-        return <T as Default>::default();
-    }
-
-    let mut ix: usize = 0;
-    let mut result: T = <T as Default>::default();
-    for _ in 0 .. n_usize {
-        let value = x[ix];
-
-        result += <T as ASumValue>::a_sum_value(value);
-
-        ix += incx_usize;
-    }
-
-    result
-}
-
-/// sums the absolute values of the elements of an `f32` vector
-pub fn sasum(n: isize, x: &[f32], incx: isize) -> f32 {
-    asum::<f32>(n, x, incx)
-}
-
-/// sums the absolute values of the elements of an `f64` vector
-pub fn dasum(n: isize, x: &[f64], incx: isize) -> f64 {
-    asum::<f64>(n, x, incx)
-}
\ No newline at end of file
+use crate::error::BlasError;
+use crate::scalar::Scalar;
+use crate::xerbla::report;
+use crate::trace::{self, Arg};
+use crate::view::VecRef;
+use crate::utils::{check_n, check_inc_positive};
+use crate::strided::walk1;
+use crate::dispatch::{self, Kernels};
+use crate::parallel::{self, chunk};
+
+/// sums the magnitudes `|re(x[i])| + |im(x[i])|` of the elements of a vector, for any `Scalar` type
+pub fn asum<T>(n: isize, x: &[T], incx: isize) -> Result<T::Real, BlasError>
+where T: Scalar,
+{
+    let n_usize = check_n(n, 1)?;
+    check_inc_positive(n_usize, x, incx, 2, "x", "incx")?;
+
+    // SAFETY: the slice was checked to hold `n` elements at increment `incx`.
+    Ok(unsafe { asum_unchecked(n_usize, x.as_ptr(), incx) })
+}
+
+/// `asum` on a raw pointer, validated by debug assertions only, like CBLAS `cblas_?asum`
+///
+/// # Safety
+///
+/// `incx` must be positive and `x` valid for reads of `(n - 1) * incx + 1` elements.
+pub unsafe fn asum_unchecked<T>(n: usize, x: *const T, incx: isize) -> T::Real
+where T: Scalar,
+{
+    debug_assert!(n == 0 || !x.is_null());
+    debug_assert!(incx > 0, "incx must be positive");
+    let kernels = dispatch::kernels::<T>();
+    if kernels.is_some_and(|k| !k.reproducible) {
+        let x = x as *mut T;
+        // SAFETY: the chunks only read, and kernels, hence `T`, are `f32` or `f64`.
+        let partials = parallel::split(n, |lo, hi| asum_serial(kernels, hi - lo, chunk(x, n, incx, lo, hi), incx));
+        if let Some(partials) = partials {
+            return partials.into_iter().fold(T::Real::zero(), |sum, p| sum + p);
+        }
+    }
+    asum_serial(kernels, n, x, incx)
+}
+
+/// `asum_unchecked` on the calling thread
+unsafe fn asum_serial<T>(kernels: Option<Kernels<T>>, n: usize, x: *const T, incx: isize) -> T::Real
+where T: Scalar,
+{
+    if let Some(kernels) = kernels {
+        match (kernels.asum, kernels.asum_strided) {
+            (Some(asum), _) if incx == 1 => return asum(n, x),
+            (_, Some(asum)) if incx != 1 => return asum(n, x, incx),
+            _ => {}
+        }
+    }
+    let mut result = T::Real::zero();
+    walk1(n, x as *mut T, incx, |px| result += (*px).abs1());
+
+    result
+}
+
+/// sums the absolute values of the elements of an `f32` vector
+pub fn sasum(n: isize, x: &[f32], incx: isize) -> Result<f32, BlasError> {
+    let call = trace::begin("sasum", &[("n", Arg::Int(n)), ("x", Arg::F32s(x)), ("incx", Arg::Int(incx))]);
+    let result = asum::<f32>(n, x, incx).map_err(|e| report("sasum", e));
+    trace::end(call, result.is_ok(), &[("result", Arg::F32(result.unwrap_or_default()))]);
+    result
+}
+
+/// sums the absolute values of the elements of an `f64` vector
+pub fn dasum(n: isize, x: &[f64], incx: isize) -> Result<f64, BlasError> {
+    let call = trace::begin("dasum", &[("n", Arg::Int(n)), ("x", Arg::F64s(x)), ("incx", Arg::Int(incx))]);
+    let result = asum::<f64>(n, x, incx).map_err(|e| report("dasum", e));
+    trace::end(call, result.is_ok(), &[("result", Arg::F64(result.unwrap_or_default()))]);
+    result
+}
+
+/// `sasum` on a raw pointer without bounds checks
+///
+/// # Safety
+///
+/// See `asum_unchecked`.
+pub unsafe fn sasum_unchecked(n: usize, x: *const f32, incx: isize) -> f32 {
+    asum_unchecked::<f32>(n, x, incx)
+}
+
+/// `dasum` on a raw pointer without bounds checks
+///
+/// # Safety
+///
+/// See `asum_unchecked`.
+pub unsafe fn dasum_unchecked(n: usize, x: *const f64, incx: isize) -> f64 {
+    asum_unchecked::<f64>(n, x, incx)
+}
+
+/// sums the magnitudes of the elements of a vector view, for any `Scalar` type
+pub fn asum_view<T>(x: VecRef<'_, T>) -> T::Real
+where T: Scalar,
+{
+    let (n, x, incx) = x.forward().as_blas();
+    asum(n, x, incx).unwrap_or_else(|_| unreachable!("views are validated on construction"))
+}
+
+/// `sasum` on a vector view
+pub fn sasum_view(x: VecRef<'_, f32>) -> f32 {
+    asum_view::<f32>(x)
+}
+
+/// `dasum` on a vector view
+pub fn dasum_view(x: VecRef<'_, f64>) -> f64 {
+    asum_view::<f64>(x)
+}
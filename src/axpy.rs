@@ -1,70 +1,129 @@
-use std::ops::{Add, Mul, AddAssign};
-use crate::utils::{check_inc, get_first_index};
-
-trait AxpyQuickReturn {
-    fn quick_return(a: Self) -> bool;
-}
-impl AxpyQuickReturn for f32 {
-    fn quick_return(a: Self) -> bool {
-        a == 0.0_f32
-    }
-}
-impl AxpyQuickReturn for f64 {
-    fn quick_return(a: Self) -> bool {
-        a == 0.0_f64
-    }
-}
-
-
-fn axpy<T>(n: isize, a: T, x: &[T], incx: isize, y: &mut [T], incy: isize) -> bool
-where T: Default + Add<Output = T> + AddAssign + Mul<Output = T> + Copy + AxpyQuickReturn,
-{
-    if check_inc(n, x, incx) != true || check_inc(n, y, incy) != true {
-        return false;
-    }
-
-    if <T as AxpyQuickReturn>::quick_return(a) {
-        return true;
-    }
-
-    let n_usize = n as usize;
-    if incx == 1 && incy == 1 {
-        for i in 0 .. n_usize {
-            y[i] += (a * x[i]);
-        }
-        return true;
-    }
-
-    let incx_abs: usize = if incx > 0 {incx as usize} else {(-incx) as usize};
-    let mut ix: usize = get_first_index(n_usize, incx);
-    
-    let incy_abs: usize = if incy > 0 {incy as usize} else {(-incy) as usize};
-    let mut iy: usize = get_first_index(n_usize, incy);
-
-    for _ in 0 .. n_usize {
-        y[iy] += a * x[ix];
-        
-        ix = if incx > 0 {
-            ix + incx_abs
-        } else {
-            ix - incx_abs
-        };
-        iy = if incy > 0 {
-            iy + incy_abs
-        } else {
-            iy - incy_abs
-        };
-    }
-
-    true
-}
-
-/// adds a scalar multiple of an `f32` vector to another `f32` vector
-pub fn saxpy(n: isize, a: f32, x: &[f32], incx: isize, y: &mut [f32], incy: isize) -> bool {
-    axpy::<f32>(n, a, x, incx, y, incy)
-}
-
-/// adds a scalar multiple of an `f64` vector to another `f64` vector
-pub fn daxpy(n: isize, a: f64, x: &[f64], incx: isize, y: &mut [f64], incy: isize) -> bool {
-    axpy::<f64>(n, a, x, incx, y, incy)
-}
\ No newline at end of file
+use crate::error::BlasError;
+use crate::scalar::Scalar;
+use crate::xerbla::report;
+use crate::trace::{self, Arg};
+use crate::view::{VecRef, VecMut, check_len};
+use crate::utils::{check_n, check_inc};
+use crate::strided::walk2;
+use crate::dispatch::{self, Kernels};
+use crate::parallel::{self, chunk};
+
+/// adds a scalar multiple of a vector to another vector, `y := a * x + y`, for any `Scalar` type
+pub fn axpy<T>(n: isize, a: T, x: &[T], incx: isize, y: &mut [T], incy: isize) -> Result<(), BlasError>
+where T: Scalar,
+{
+    let n_usize = check_n(n, 1)?;
+    check_inc(n_usize, x, incx, 3, "x", "incx")?;
+    check_inc(n_usize, y, incy, 5, "y", "incy")?;
+
+    // SAFETY: both slices were checked to hold `n` elements at their increments, and `&mut y` cannot alias `x`.
+    unsafe { axpy_unchecked(n_usize, a, x.as_ptr(), incx, y.as_mut_ptr(), incy) };
+    Ok(())
+}
+
+/// `axpy` on raw pointers, validated by debug assertions only, like CBLAS `cblas_?axpy`
+///
+/// As in BLAS, a negative increment walks the array from its end: element `i` of `x` is `x[(n - 1 - i) * |incx|]`.
+///
+/// # Safety
+///
+/// `x` must be valid for reads of `(n - 1) * |incx| + 1` elements, `y` for reads and writes of
+/// `(n - 1) * |incy| + 1` elements, and the two ranges must not overlap.
+pub unsafe fn axpy_unchecked<T>(n: usize, a: T, x: *const T, incx: isize, y: *mut T, incy: isize)
+where T: Scalar,
+{
+    debug_assert!(n == 0 || (!x.is_null() && !y.is_null()));
+    debug_assert!(n <= 1 || incy != 0, "incy must be nonzero");
+    if n == 0 || a == T::zero() {
+        return;
+    }
+
+    let kernels = dispatch::kernels::<T>();
+    if kernels.is_some() {
+        let (x, y) = (x as *mut T, y);
+        // SAFETY: the chunks write disjoint parts of `y`, and kernels, hence `T`, are `f32` or `f64`.
+        let done = parallel::split(n, |lo, hi| {
+            axpy_serial(kernels, hi - lo, a, chunk(x, n, incx, lo, hi), incx, chunk(y, n, incy, lo, hi), incy)
+        });
+        if done.is_some() {
+            return;
+        }
+    }
+    axpy_serial(kernels, n, a, x, incx, y, incy)
+}
+
+/// `axpy_unchecked` on the calling thread
+unsafe fn axpy_serial<T>(kernels: Option<Kernels<T>>, n: usize, a: T, x: *const T, incx: isize, y: *mut T,
+                         incy: isize)
+where T: Scalar,
+{
+    if let Some(kernels) = kernels {
+        if incx == 1 && incy == 1 {
+            return (kernels.axpy)(n, a, x, y);
+        }
+        if let Some(axpy) = kernels.axpy_strided {
+            return axpy(n, a, x, incx, y, incy);
+        }
+    }
+    walk2(n, x as *mut T, incx, y, incy, |px, py| *py += a * *px);
+}
+
+/// adds a scalar multiple of an `f32` vector to another `f32` vector
+pub fn saxpy(n: isize, a: f32, x: &[f32], incx: isize, y: &mut [f32], incy: isize) -> Result<(), BlasError> {
+    let call = trace::begin("saxpy", &[
+        ("n", Arg::Int(n)), ("alpha", Arg::F32(a)), ("x", Arg::F32s(x)), ("incx", Arg::Int(incx)),
+        ("y", Arg::F32s(y)), ("incy", Arg::Int(incy)),
+    ]);
+    let result = axpy::<f32>(n, a, x, incx, y, incy).map_err(|e| report("saxpy", e));
+    trace::end(call, result.is_ok(), &[("y", Arg::F32s(y))]);
+    result
+}
+
+/// adds a scalar multiple of an `f64` vector to another `f64` vector
+pub fn daxpy(n: isize, a: f64, x: &[f64], incx: isize, y: &mut [f64], incy: isize) -> Result<(), BlasError> {
+    let call = trace::begin("daxpy", &[
+        ("n", Arg::Int(n)), ("alpha", Arg::F64(a)), ("x", Arg::F64s(x)), ("incx", Arg::Int(incx)),
+        ("y", Arg::F64s(y)), ("incy", Arg::Int(incy)),
+    ]);
+    let result = axpy::<f64>(n, a, x, incx, y, incy).map_err(|e| report("daxpy", e));
+    trace::end(call, result.is_ok(), &[("y", Arg::F64s(y))]);
+    result
+}
+
+/// `saxpy` on raw pointers without bounds checks
+///
+/// # Safety
+///
+/// See `axpy_unchecked`.
+pub unsafe fn saxpy_unchecked(n: usize, a: f32, x: *const f32, incx: isize, y: *mut f32, incy: isize) {
+    axpy_unchecked::<f32>(n, a, x, incx, y, incy)
+}
+
+/// `daxpy` on raw pointers without bounds checks
+///
+/// # Safety
+///
+/// See `axpy_unchecked`.
+pub unsafe fn daxpy_unchecked(n: usize, a: f64, x: *const f64, incx: isize, y: *mut f64, incy: isize) {
+    axpy_unchecked::<f64>(n, a, x, incx, y, incy)
+}
+
+/// adds a scalar multiple of a vector view to another, `y := a * x + y`, for any `Scalar` type
+pub fn axpy_view<T>(a: T, x: VecRef<'_, T>, y: &mut VecMut<'_, T>) -> Result<(), BlasError>
+where T: Scalar,
+{
+    check_len(x.len(), y.len(), 3, "y")?;
+    let (n, x, incx) = x.as_blas();
+    let (_, y, incy) = y.as_blas();
+    axpy(n, a, x, incx, y, incy)
+}
+
+/// `saxpy` on vector views
+pub fn saxpy_view(a: f32, x: VecRef<'_, f32>, y: &mut VecMut<'_, f32>) -> Result<(), BlasError> {
+    axpy_view::<f32>(a, x, y).map_err(|e| report("saxpy_view", e))
+}
+
+/// `daxpy` on vector views
+pub fn daxpy_view(a: f64, x: VecRef<'_, f64>, y: &mut VecMut<'_, f64>) -> Result<(), BlasError> {
+    axpy_view::<f64>(a, x, y).map_err(|e| report("daxpy_view", e))
+}
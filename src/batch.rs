@@ -0,0 +1,576 @@
+use std::mem;
+use std::thread;
+use crate::axpy::axpy_unchecked;
+use crate::context::Context;
+use crate::copy::copy_unchecked;
+use crate::dot::dot_unchecked;
+use crate::nrm2::nrm2_unchecked;
+use crate::error::BlasError;
+use crate::rot::rot_unchecked;
+use crate::scal::scal_unchecked;
+use crate::scalar::Scalar;
+use crate::xerbla::report;
+use crate::utils::{check_n, check_len, required_len};
+
+// Batched routines follow MKL's `cblas_?axpy_batch` layout: the problems are split into `group_size.len()`
+// groups, group `g` holding `group_size[g]` problems that share the dimension `n[g]`, the increments and the
+// scalars, while the vectors are listed problem by problem, group after group. Arguments are validated once
+// per group, then every problem runs on the unchecked kernel.
+
+/// checks that every per-group array has one entry per group and returns the total number of problems
+fn check_groups(group_size: &[usize], per_group: &[(usize, &'static str, usize)]) -> Result<usize, BlasError> {
+    let expected = group_size.len();
+    for &(pos, name, actual) in per_group {
+        if actual != expected {
+            return Err(BlasError::DimensionMismatch { pos, name, expected, actual });
+        }
+    }
+    Ok(group_size.iter().sum())
+}
+
+/// checks that every per-problem array has one entry per problem
+fn check_problems(total: usize, per_problem: &[(usize, &'static str, usize)]) -> Result<(), BlasError> {
+    for &(pos, name, actual) in per_problem {
+        if actual != total {
+            return Err(BlasError::DimensionMismatch { pos, name, expected: total, actual });
+        }
+    }
+    Ok(())
+}
+
+fn check_inc_nonzero(inc: isize, pos: usize, name: &'static str) -> Result<(), BlasError> {
+    if inc == 0 {
+        return Err(BlasError::InvalidIncrement { pos, name, value: inc });
+    }
+    Ok(())
+}
+
+/// runs `f` on every validated problem, spread over the threads the current `Context` allows when the batch
+/// holds `work` elements, at least the context's parallel threshold per thread
+fn run_batch<P, F>(mut problems: Vec<P>, work: usize, f: F)
+where P: Send, F: Fn(P) + Sync,
+{
+    let ctx = Context::current();
+    let threads = ctx.max_threads().min(work / ctx.parallel_threshold()).min(problems.len());
+    if threads <= 1 {
+        problems.into_iter().for_each(f);
+        return;
+    }
+    let chunk = problems.len().div_ceil(threads);
+    // each worker is one of the context's threads, the routines it runs stay on it
+    let worker_ctx = ctx.with_max_threads(1);
+    let (f, worker_ctx) = (&f, &worker_ctx);
+    thread::scope(|scope| {
+        while !problems.is_empty() {
+            let rest = problems.split_off(chunk.min(problems.len()));
+            let mine = mem::replace(&mut problems, rest);
+            scope.spawn(move || worker_ctx.install(|| mine.into_iter().for_each(f)));
+        }
+    });
+}
+
+/// computes `y := alpha * x + y` for every problem of a batch, for any `Scalar` type
+pub fn axpy_batch<T>(n: &[isize], alpha: &[T], x: &[&[T]], incx: &[isize], y: &mut [&mut [T]], incy: &[isize],
+                     group_size: &[usize]) -> Result<(), BlasError>
+where T: Scalar + Send + Sync,
+{
+    let total = check_groups(group_size, &[(1, "n", n.len()), (2, "alpha", alpha.len()), (4, "incx", incx.len()),
+                                           (6, "incy", incy.len())])?;
+    check_problems(total, &[(3, "x", x.len()), (5, "y", y.len())])?;
+
+    let mut problems = Vec::with_capacity(total);
+    let mut work = 0;
+    let mut members = x.iter().zip(y.iter_mut());
+    for (g, &size) in group_size.iter().enumerate() {
+        let n_usize = check_n(n[g], 1)?;
+        check_inc_nonzero(incx[g], 4, "incx")?;
+        check_inc_nonzero(incy[g], 6, "incy")?;
+        for (xi, yi) in members.by_ref().take(size) {
+            check_len(n_usize, xi, incx[g], 3, "x")?;
+            check_len(n_usize, yi, incy[g], 5, "y")?;
+            problems.push((n_usize, alpha[g], *xi, incx[g], &mut **yi, incy[g]));
+        }
+        work += n_usize * size;
+    }
+
+    run_batch(problems, work, |(n, a, x, incx, y, incy)| {
+        // SAFETY: both vectors were checked to hold `n` elements at their increments.
+        unsafe { axpy_unchecked(n, a, x.as_ptr(), incx, y.as_mut_ptr(), incy) }
+    });
+    Ok(())
+}
+
+/// `saxpy` over a batch of `f32` problems
+pub fn saxpy_batch(n: &[isize], alpha: &[f32], x: &[&[f32]], incx: &[isize], y: &mut [&mut [f32]], incy: &[isize],
+                   group_size: &[usize]) -> Result<(), BlasError> {
+    axpy_batch::<f32>(n, alpha, x, incx, y, incy, group_size).map_err(|e| report("saxpy_batch", e))
+}
+
+/// `daxpy` over a batch of `f64` problems
+pub fn daxpy_batch(n: &[isize], alpha: &[f64], x: &[&[f64]], incx: &[isize], y: &mut [&mut [f64]], incy: &[isize],
+                   group_size: &[usize]) -> Result<(), BlasError> {
+    axpy_batch::<f64>(n, alpha, x, incx, y, incy, group_size).map_err(|e| report("daxpy_batch", e))
+}
+
+/// copies `x` into `y` for every problem of a batch, for any `Copy` element type
+pub fn copy_batch<T>(n: &[isize], x: &[&[T]], incx: &[isize], y: &mut [&mut [T]], incy: &[isize],
+                     group_size: &[usize]) -> Result<(), BlasError>
+where T: Copy + Send + Sync,
+{
+    let total = check_groups(group_size, &[(1, "n", n.len()), (3, "incx", incx.len()), (5, "incy", incy.len())])?;
+    check_problems(total, &[(2, "x", x.len()), (4, "y", y.len())])?;
+
+    let mut problems = Vec::with_capacity(total);
+    let mut work = 0;
+    let mut members = x.iter().zip(y.iter_mut());
+    for (g, &size) in group_size.iter().enumerate() {
+        let n_usize = check_n(n[g], 1)?;
+        check_inc_nonzero(incx[g], 3, "incx")?;
+        check_inc_nonzero(incy[g], 5, "incy")?;
+        for (xi, yi) in members.by_ref().take(size) {
+            check_len(n_usize, xi, incx[g], 2, "x")?;
+            check_len(n_usize, yi, incy[g], 4, "y")?;
+            problems.push((n_usize, *xi, incx[g], &mut **yi, incy[g]));
+        }
+        work += n_usize * size;
+    }
+
+    run_batch(problems, work, |(n, x, incx, y, incy)| {
+        // SAFETY: both vectors were checked to hold `n` elements at their increments.
+        unsafe { copy_unchecked(n, x.as_ptr(), incx, y.as_mut_ptr(), incy) }
+    });
+    Ok(())
+}
+
+/// `scopy` over a batch of `f32` problems
+pub fn scopy_batch(n: &[isize], x: &[&[f32]], incx: &[isize], y: &mut [&mut [f32]], incy: &[isize],
+                   group_size: &[usize]) -> Result<(), BlasError> {
+    copy_batch::<f32>(n, x, incx, y, incy, group_size).map_err(|e| report("scopy_batch", e))
+}
+
+/// `dcopy` over a batch of `f64` problems
+pub fn dcopy_batch(n: &[isize], x: &[&[f64]], incx: &[isize], y: &mut [&mut [f64]], incy: &[isize],
+                   group_size: &[usize]) -> Result<(), BlasError> {
+    copy_batch::<f64>(n, x, incx, y, incy, group_size).map_err(|e| report("dcopy_batch", e))
+}
+
+/// computes the unconjugated dot product of `x` and `y` for every problem of a batch into `result`, for any
+/// `Scalar` type
+pub fn dot_batch<T>(n: &[isize], x: &[&[T]], incx: &[isize], y: &[&[T]], incy: &[isize], group_size: &[usize],
+                    result: &mut [T]) -> Result<(), BlasError>
+where T: Scalar + Send + Sync,
+{
+    let total = check_groups(group_size, &[(1, "n", n.len()), (3, "incx", incx.len()), (5, "incy", incy.len())])?;
+    check_problems(total, &[(2, "x", x.len()), (4, "y", y.len()), (7, "result", result.len())])?;
+
+    let mut problems = Vec::with_capacity(total);
+    let mut work = 0;
+    let mut members = x.iter().zip(y.iter()).zip(result.iter_mut());
+    for (g, &size) in group_size.iter().enumerate() {
+        let n_usize = check_n(n[g], 1)?;
+        check_inc_nonzero(incx[g], 3, "incx")?;
+        check_inc_nonzero(incy[g], 5, "incy")?;
+        for ((xi, yi), ri) in members.by_ref().take(size) {
+            check_len(n_usize, xi, incx[g], 2, "x")?;
+            check_len(n_usize, yi, incy[g], 4, "y")?;
+            problems.push((n_usize, *xi, incx[g], *yi, incy[g], ri));
+        }
+        work += n_usize * size;
+    }
+
+    run_batch(problems, work, |(n, x, incx, y, incy, r)| {
+        // SAFETY: both vectors were checked to hold `n` elements at their increments.
+        *r = unsafe { dot_unchecked(n, x.as_ptr(), incx, y.as_ptr(), incy) }
+    });
+    Ok(())
+}
+
+/// `sdot` over a batch of `f32` problems
+pub fn sdot_batch(n: &[isize], x: &[&[f32]], incx: &[isize], y: &[&[f32]], incy: &[isize], group_size: &[usize],
+                  result: &mut [f32]) -> Result<(), BlasError> {
+    dot_batch::<f32>(n, x, incx, y, incy, group_size, result).map_err(|e| report("sdot_batch", e))
+}
+
+/// `ddot` over a batch of `f64` problems
+pub fn ddot_batch(n: &[isize], x: &[&[f64]], incx: &[isize], y: &[&[f64]], incy: &[isize], group_size: &[usize],
+                  result: &mut [f64]) -> Result<(), BlasError> {
+    dot_batch::<f64>(n, x, incx, y, incy, group_size, result).map_err(|e| report("ddot_batch", e))
+}
+
+/// applies the plane rotation `(c, s)` to `x` and `y` for every problem of a batch, for any `Scalar` type
+pub fn rot_batch<T>(n: &[isize], x: &mut [&mut [T]], incx: &[isize], y: &mut [&mut [T]], incy: &[isize], c: &[T],
+                    s: &[T], group_size: &[usize]) -> Result<(), BlasError>
+where T: Scalar + Send + Sync,
+{
+    let total = check_groups(group_size, &[(1, "n", n.len()), (3, "incx", incx.len()), (5, "incy", incy.len()),
+                                           (6, "c", c.len()), (7, "s", s.len())])?;
+    check_problems(total, &[(2, "x", x.len()), (4, "y", y.len())])?;
+
+    let mut problems = Vec::with_capacity(total);
+    let mut work = 0;
+    let mut members = x.iter_mut().zip(y.iter_mut());
+    for (g, &size) in group_size.iter().enumerate() {
+        let n_usize = check_n(n[g], 1)?;
+        check_inc_nonzero(incx[g], 3, "incx")?;
+        check_inc_nonzero(incy[g], 5, "incy")?;
+        for (xi, yi) in members.by_ref().take(size) {
+            check_len(n_usize, xi, incx[g], 2, "x")?;
+            check_len(n_usize, yi, incy[g], 4, "y")?;
+            problems.push((n_usize, &mut **xi, incx[g], &mut **yi, incy[g], c[g], s[g]));
+        }
+        work += n_usize * size;
+    }
+
+    run_batch(problems, work, |(n, x, incx, y, incy, c, s)| {
+        // SAFETY: both vectors were checked to hold `n` elements at their increments, and cannot alias.
+        unsafe { rot_unchecked(n, x.as_mut_ptr(), incx, y.as_mut_ptr(), incy, c, s) }
+    });
+    Ok(())
+}
+
+/// `srot` over a batch of `f32` problems
+pub fn srot_batch(n: &[isize], x: &mut [&mut [f32]], incx: &[isize], y: &mut [&mut [f32]], incy: &[isize], c: &[f32],
+                  s: &[f32], group_size: &[usize]) -> Result<(), BlasError> {
+    rot_batch::<f32>(n, x, incx, y, incy, c, s, group_size).map_err(|e| report("srot_batch", e))
+}
+
+/// `drot` over a batch of `f64` problems
+pub fn drot_batch(n: &[isize], x: &mut [&mut [f64]], incx: &[isize], y: &mut [&mut [f64]], incy: &[isize], c: &[f64],
+                  s: &[f64], group_size: &[usize]) -> Result<(), BlasError> {
+    rot_batch::<f64>(n, x, incx, y, incy, c, s, group_size).map_err(|e| report("drot_batch", e))
+}
+
+/// scales `x` by `alpha` for every problem of a batch, for any `Scalar` type
+pub fn scal_batch<T>(n: &[isize], alpha: &[T], x: &mut [&mut [T]], incx: &[isize], group_size: &[usize])
+                     -> Result<(), BlasError>
+where T: Scalar + Send + Sync,
+{
+    let total = check_groups(group_size, &[(1, "n", n.len()), (2, "alpha", alpha.len()), (4, "incx", incx.len())])?;
+    check_problems(total, &[(3, "x", x.len())])?;
+
+    let mut problems = Vec::with_capacity(total);
+    let mut work = 0;
+    let mut members = x.iter_mut();
+    for (g, &size) in group_size.iter().enumerate() {
+        let n_usize = check_n(n[g], 1)?;
+        if incx[g] <= 0 {
+            return Err(BlasError::InvalidIncrement { pos: 4, name: "incx", value: incx[g] });
+        }
+        for xi in members.by_ref().take(size) {
+            check_len(n_usize, xi, incx[g], 3, "x")?;
+            problems.push((n_usize, alpha[g], &mut **xi, incx[g]));
+        }
+        work += n_usize * size;
+    }
+
+    run_batch(problems, work, |(n, a, x, incx)| {
+        // SAFETY: the vector was checked to hold `n` elements at its positive increment.
+        unsafe { scal_unchecked(n, a, x.as_mut_ptr(), incx) }
+    });
+    Ok(())
+}
+
+/// `sscal` over a batch of `f32` problems
+pub fn sscal_batch(n: &[isize], alpha: &[f32], x: &mut [&mut [f32]], incx: &[isize], group_size: &[usize])
+                   -> Result<(), BlasError> {
+    scal_batch::<f32>(n, alpha, x, incx, group_size).map_err(|e| report("sscal_batch", e))
+}
+
+/// `dscal` over a batch of `f64` problems
+pub fn dscal_batch(n: &[isize], alpha: &[f64], x: &mut [&mut [f64]], incx: &[isize], group_size: &[usize])
+                   -> Result<(), BlasError> {
+    scal_batch::<f64>(n, alpha, x, incx, group_size).map_err(|e| report("dscal_batch", e))
+}
+
+// Strided batches hold every problem in one buffer: problem `k` is the vector starting at `k * stride`, all
+// problems sharing `n`, the increment and the scalars. Vectors that are written must not overlap, so their
+// stride must be at least the span `(n - 1) * |inc| + 1` of one vector.
+
+/// validates a strided batch of vectors of `n` elements in `x`, with the vector at position `pos` followed by
+/// its increment and stride, and returns the span of one vector
+fn check_strided<T>(n: usize, x: &[T], inc: isize, stride: usize, batch_size: usize, pos: usize,
+                    names: (&'static str, &'static str, &'static str), positive: bool, writable: bool)
+                    -> Result<usize, BlasError> {
+    let (name, inc_name, stride_name) = names;
+    if inc == 0 || (positive && inc < 0) {
+        return Err(BlasError::InvalidIncrement { pos: pos + 1, name: inc_name, value: inc });
+    }
+    let span = required_len(n, inc);
+    if writable && batch_size > 1 && stride < span {
+        return Err(BlasError::Overlap { pos: pos + 2, name: stride_name });
+    }
+    let required = match batch_size {
+        0 => 0,
+        _ => stride.saturating_mul(batch_size - 1).saturating_add(span),
+    };
+    if x.len() < required {
+        return Err(BlasError::SliceTooShort { pos, name, required, actual: x.len() });
+    }
+    Ok(span)
+}
+
+fn check_result_len<T>(result: &[T], batch_size: usize, pos: usize) -> Result<(), BlasError> {
+    if result.len() < batch_size {
+        return Err(BlasError::SliceTooShort { pos, name: "result", required: batch_size, actual: result.len() });
+    }
+    Ok(())
+}
+
+/// the `batch_size` vectors of `span` elements at multiples of `stride` in `x`
+fn members<T>(x: &[T], stride: usize, span: usize, batch_size: usize) -> impl Iterator<Item = &[T]> {
+    (0 .. batch_size).map(move |k| &x[k * stride .. k * stride + span])
+}
+
+/// mutable counterpart of `members`, the vectors being disjoint since `stride >= span`
+fn members_mut<T>(mut x: &mut [T], stride: usize, span: usize, batch_size: usize) -> Vec<&mut [T]> {
+    let mut members = Vec::with_capacity(batch_size);
+    for k in 0 .. batch_size {
+        let (head, tail) = mem::take(&mut x).split_at_mut(if k + 1 < batch_size { stride } else { span });
+        members.push(&mut head[.. span]);
+        x = tail;
+    }
+    members
+}
+
+/// computes `y := alpha * x + y` for every problem of a strided batch, for any `Scalar` type
+pub fn axpy_batch_strided<T>(n: isize, alpha: T, x: &[T], incx: isize, stridex: usize, y: &mut [T], incy: isize,
+                             stridey: usize, batch_size: usize) -> Result<(), BlasError>
+where T: Scalar + Send + Sync,
+{
+    let n_usize = check_n(n, 1)?;
+    let spanx = check_strided(n_usize, x, incx, stridex, batch_size, 3, ("x", "incx", "stridex"), false, false)?;
+    let spany = check_strided(n_usize, y, incy, stridey, batch_size, 6, ("y", "incy", "stridey"), false, true)?;
+    if n_usize == 0 || batch_size == 0 {
+        return Ok(());
+    }
+
+    let problems: Vec<_> = members(x, stridex, spanx, batch_size)
+        .zip(members_mut(y, stridey, spany, batch_size))
+        .collect();
+    run_batch(problems, n_usize * batch_size, |(x, y)| {
+        // SAFETY: both vectors span exactly `n` elements at their increments.
+        unsafe { axpy_unchecked(n_usize, alpha, x.as_ptr(), incx, y.as_mut_ptr(), incy) }
+    });
+    Ok(())
+}
+
+/// `saxpy` over a strided batch of `f32` problems
+pub fn saxpy_batch_strided(n: isize, alpha: f32, x: &[f32], incx: isize, stridex: usize, y: &mut [f32], incy: isize,
+                           stridey: usize, batch_size: usize) -> Result<(), BlasError> {
+    axpy_batch_strided::<f32>(n, alpha, x, incx, stridex, y, incy, stridey, batch_size)
+        .map_err(|e| report("saxpy_batch_strided", e))
+}
+
+/// `daxpy` over a strided batch of `f64` problems
+pub fn daxpy_batch_strided(n: isize, alpha: f64, x: &[f64], incx: isize, stridex: usize, y: &mut [f64], incy: isize,
+                           stridey: usize, batch_size: usize) -> Result<(), BlasError> {
+    axpy_batch_strided::<f64>(n, alpha, x, incx, stridex, y, incy, stridey, batch_size)
+        .map_err(|e| report("daxpy_batch_strided", e))
+}
+
+/// computes the unconjugated dot product of every problem of a strided batch into `result[k]`, for any
+/// `Scalar` type
+pub fn dot_batch_strided<T>(n: isize, x: &[T], incx: isize, stridex: usize, y: &[T], incy: isize, stridey: usize,
+                            result: &mut [T], batch_size: usize) -> Result<(), BlasError>
+where T: Scalar + Send + Sync,
+{
+    let n_usize = check_n(n, 1)?;
+    let spanx = check_strided(n_usize, x, incx, stridex, batch_size, 2, ("x", "incx", "stridex"), false, false)?;
+    let spany = check_strided(n_usize, y, incy, stridey, batch_size, 5, ("y", "incy", "stridey"), false, false)?;
+    check_result_len(result, batch_size, 8)?;
+
+    let problems: Vec<_> = members(x, stridex, spanx, batch_size)
+        .zip(members(y, stridey, spany, batch_size))
+        .zip(result.iter_mut())
+        .collect();
+    run_batch(problems, n_usize * batch_size, |((x, y), r)| {
+        // SAFETY: both vectors span exactly `n` elements at their increments.
+        *r = unsafe { dot_unchecked(n_usize, x.as_ptr(), incx, y.as_ptr(), incy) }
+    });
+    Ok(())
+}
+
+/// `sdot` over a strided batch of `f32` problems
+pub fn sdot_batch_strided(n: isize, x: &[f32], incx: isize, stridex: usize, y: &[f32], incy: isize, stridey: usize,
+                          result: &mut [f32], batch_size: usize) -> Result<(), BlasError> {
+    dot_batch_strided::<f32>(n, x, incx, stridex, y, incy, stridey, result, batch_size)
+        .map_err(|e| report("sdot_batch_strided", e))
+}
+
+/// `ddot` over a strided batch of `f64` problems
+pub fn ddot_batch_strided(n: isize, x: &[f64], incx: isize, stridex: usize, y: &[f64], incy: isize, stridey: usize,
+                          result: &mut [f64], batch_size: usize) -> Result<(), BlasError> {
+    dot_batch_strided::<f64>(n, x, incx, stridex, y, incy, stridey, result, batch_size)
+        .map_err(|e| report("ddot_batch_strided", e))
+}
+
+/// scales every problem of a strided batch by `alpha`, for any `Scalar` type
+pub fn scal_batch_strided<T>(n: isize, alpha: T, x: &mut [T], incx: isize, stridex: usize, batch_size: usize)
+                             -> Result<(), BlasError>
+where T: Scalar + Send + Sync,
+{
+    let n_usize = check_n(n, 1)?;
+    let spanx = check_strided(n_usize, x, incx, stridex, batch_size, 3, ("x", "incx", "stridex"), true, true)?;
+    if n_usize == 0 || batch_size == 0 {
+        return Ok(());
+    }
+
+    let problems = members_mut(x, stridex, spanx, batch_size);
+    run_batch(problems, n_usize * batch_size, |x| {
+        // SAFETY: the vector spans exactly `n` elements at its positive increment.
+        unsafe { scal_unchecked(n_usize, alpha, x.as_mut_ptr(), incx) }
+    });
+    Ok(())
+}
+
+/// `sscal` over a strided batch of `f32` problems
+pub fn sscal_batch_strided(n: isize, alpha: f32, x: &mut [f32], incx: isize, stridex: usize, batch_size: usize)
+                           -> Result<(), BlasError> {
+    scal_batch_strided::<f32>(n, alpha, x, incx, stridex, batch_size).map_err(|e| report("sscal_batch_strided", e))
+}
+
+/// `dscal` over a strided batch of `f64` problems
+pub fn dscal_batch_strided(n: isize, alpha: f64, x: &mut [f64], incx: isize, stridex: usize, batch_size: usize)
+                           -> Result<(), BlasError> {
+    scal_batch_strided::<f64>(n, alpha, x, incx, stridex, batch_size).map_err(|e| report("dscal_batch_strided", e))
+}
+
+/// computes the Euclidean norm of every problem of a strided batch into `result[k]`, for any `Scalar` type
+pub fn nrm2_batch_strided<T>(n: isize, x: &[T], incx: isize, stridex: usize, result: &mut [T::Real],
+                             batch_size: usize) -> Result<(), BlasError>
+where T: Scalar + Send + Sync, T::Real: Send,
+{
+    let n_usize = check_n(n, 1)?;
+    let spanx = check_strided(n_usize, x, incx, stridex, batch_size, 2, ("x", "incx", "stridex"), true, false)?;
+    check_result_len(result, batch_size, 5)?;
+
+    let problems: Vec<_> = members(x, stridex, spanx, batch_size).zip(result.iter_mut()).collect();
+    run_batch(problems, n_usize * batch_size, |(x, r)| {
+        // SAFETY: the vector spans exactly `n` elements at its positive increment.
+        *r = unsafe { nrm2_unchecked(n_usize, x.as_ptr(), incx) }
+    });
+    Ok(())
+}
+
+/// `snrm2` over a strided batch of `f32` problems
+pub fn snrm2_batch_strided(n: isize, x: &[f32], incx: isize, stridex: usize, result: &mut [f32], batch_size: usize)
+                           -> Result<(), BlasError> {
+    nrm2_batch_strided::<f32>(n, x, incx, stridex, result, batch_size).map_err(|e| report("snrm2_batch_strided", e))
+}
+
+/// `dnrm2` over a strided batch of `f64` problems
+pub fn dnrm2_batch_strided(n: isize, x: &[f64], incx: isize, stridex: usize, result: &mut [f64], batch_size: usize)
+                           -> Result<(), BlasError> {
+    nrm2_batch_strided::<f64>(n, x, incx, stridex, result, batch_size).map_err(|e| report("dnrm2_batch_strided", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{daxpy, ddot, drot, snrm2};
+
+    #[test]
+    fn batches_match_one_call_per_problem() {
+        let xs: Vec<Vec<f64>> = (0 .. 5).map(|k| (0 .. 8).map(|i| (k * 8 + i) as f64).collect()).collect();
+        let x: Vec<&[f64]> = xs.iter().map(|v| &v[..]).collect();
+        let mut ys = vec![vec![1.0_f64; 8]; 5];
+        let mut expected = ys.clone();
+
+        // two groups: three problems of length 8 with unit strides, two of length 4 with stride -2
+        let (n, alpha, inc, group_size) = ([8, 4], [2.0, -1.0], [1, -2], [3, 2]);
+        let mut y: Vec<&mut [f64]> = ys.iter_mut().map(|v| &mut v[..]).collect();
+        daxpy_batch(&n, &alpha, &x, &inc, &mut y, &inc, &group_size).unwrap();
+        for (k, e) in expected.iter_mut().enumerate() {
+            let g = if k < 3 { 0 } else { 1 };
+            daxpy(n[g], alpha[g], x[k], inc[g], e, inc[g]).unwrap();
+        }
+        assert_eq!(ys, expected);
+
+        let y: Vec<&[f64]> = ys.iter().map(|v| &v[..]).collect();
+        let mut dots = [0.0; 5];
+        ddot_batch(&n, &x, &inc, &y, &inc, &group_size, &mut dots).unwrap();
+        for k in 0 .. 5 {
+            let g = if k < 3 { 0 } else { 1 };
+            assert_eq!(Ok(dots[k]), ddot(n[g], x[k], inc[g], y[k], inc[g]));
+        }
+
+        let mut xs2 = xs.clone();
+        let mut x2: Vec<&mut [f64]> = xs2.iter_mut().map(|v| &mut v[..]).collect();
+        let mut y: Vec<&mut [f64]> = ys.iter_mut().map(|v| &mut v[..]).collect();
+        drot_batch(&[8], &mut x2[.. 1], &[1], &mut y[.. 1], &[1], &[0.0], &[1.0], &[1]).unwrap();
+        let (mut x0, mut y0) = (xs[0].clone(), expected[0].clone());
+        drot(8, &mut x0, 1, &mut y0, 1, 0.0, 1.0).unwrap();
+        assert_eq!((&xs2[0], &ys[0]), (&x0, &y0));
+    }
+
+    #[test]
+    fn large_batches_are_spread_over_threads() {
+        let mut xs = vec![vec![1.0_f64; 1000]; 4];
+        let mut x: Vec<&mut [f64]> = xs.iter_mut().map(|v| &mut v[..]).collect();
+        Context::new().with_max_threads(4).with_parallel_threshold(1000).install(|| {
+            dscal_batch(&[1000, 1000], &[2.0, 3.0], &mut x, &[1, 1], &[3, 1])
+        }).unwrap();
+        assert!(xs[.. 3].iter().all(|v| v.iter().all(|&e| e == 2.0)));
+        assert!(xs[3].iter().all(|&e| e == 3.0));
+    }
+
+    #[test]
+    fn batch_arguments_are_checked_per_group() {
+        let x = [1.0_f32; 4];
+        let mut y = [0.0_f32; 4];
+        let mut ys: Vec<&mut [f32]> = vec![&mut y[..]];
+        assert_eq!(saxpy_batch(&[4], &[1.0], &[&x, &x], &[1], &mut ys, &[1], &[2]),
+                   Err(BlasError::DimensionMismatch { pos: 5, name: "y", expected: 2, actual: 1 }));
+        assert_eq!(saxpy_batch(&[4], &[1.0], &[&x], &[0], &mut ys, &[1], &[1]),
+                   Err(BlasError::InvalidIncrement { pos: 4, name: "incx", value: 0 }));
+        assert_eq!(saxpy_batch(&[5], &[1.0], &[&x], &[1], &mut ys, &[1], &[1]),
+                   Err(BlasError::SliceTooShort { pos: 3, name: "x", required: 5, actual: 4 }));
+        assert_eq!(sscal_batch(&[4, 4], &[1.0], &mut ys, &[1, 1], &[1, 0]),
+                   Err(BlasError::DimensionMismatch { pos: 2, name: "alpha", expected: 2, actual: 1 }));
+    }
+
+    #[test]
+    fn strided_batches_match_one_call_per_problem() {
+        // three problems of length 3 at stride 4 in x and stride 3 in y, y walked backwards
+        let x: Vec<f32> = (0 .. 12).map(|i| i as f32).collect();
+        let mut y = vec![1.0_f32; 9];
+        let mut expected = y.clone();
+        saxpy_batch_strided(3, 2.0, &x, 1, 4, &mut y, -1, 3, 3).unwrap();
+        for k in 0 .. 3 {
+            crate::saxpy(3, 2.0, &x[4 * k ..], 1, &mut expected[3 * k ..], -1).unwrap();
+        }
+        assert_eq!(y, expected);
+
+        let mut dots = [0.0; 3];
+        sdot_batch_strided(3, &x, 1, 4, &y, -1, 3, &mut dots, 3).unwrap();
+        assert_eq!(Ok(dots[1]), crate::sdot(3, &x[4 ..], 1, &y[3 ..], -1));
+
+        let mut norms = [0.0; 2];
+        snrm2_batch_strided(2, &[3.0, 0.0, 4.0, 6.0, 0.0, 8.0], 2, 3, &mut norms, 2).unwrap();
+        assert_eq!(norms, [5.0, 10.0]);
+        assert_eq!(snrm2(2, &x[4 ..], 2), Ok((4.0_f32 * 4.0 + 36.0).sqrt()));
+
+        sscal_batch_strided(2, -1.0, &mut y, 2, 6, 2).unwrap();
+        assert_eq!(y[2], -expected[2]);
+        assert_eq!(y[1], expected[1]);
+
+        assert_eq!(sscal_batch_strided(3, 2.0, &mut [], 1, 3, 0), Ok(()));
+        assert_eq!(sscal_batch_strided(3, 2.0, &mut y, 1, 2, 2), Err(BlasError::Overlap { pos: 5, name: "stridex" }));
+        assert_eq!(sdot_batch_strided(3, &x, 1, 5, &y, 1, 3, &mut dots, 3),
+                   Err(BlasError::SliceTooShort { pos: 2, name: "x", required: 13, actual: 12 }));
+    }
+
+    #[test]
+    fn batch_threads_follow_the_context_threshold() {
+        let threads = |threshold: usize| {
+            let ids = std::sync::Mutex::new(std::collections::HashSet::new());
+            let ctx = Context::new().with_max_threads(4).with_parallel_threshold(threshold);
+            ctx.install(|| run_batch((0 .. 8).collect(), 8 * 100, |_: usize| {
+                ids.lock().unwrap().insert(thread::current().id());
+            }));
+            ids.into_inner().unwrap().len()
+        };
+        assert_eq!(threads(1000), 1);
+        assert!(threads(100) > 1);
+    }
+}
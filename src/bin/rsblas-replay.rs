@@ -0,0 +1,34 @@
+//! Re-executes a trace written by `rsblas::record_to_file` and compares the outputs with the recorded ones.
+//!
+//! Usage: `rsblas-replay TRACE`. Exits with status 1 when an output differs, 2 when the trace cannot be read.
+
+use std::process;
+
+fn main() {
+    let path = match std::env::args().nth(1) {
+        Some(path) => path,
+        None => {
+            eprintln!("usage: rsblas-replay TRACE");
+            process::exit(2);
+        }
+    };
+    let report = match rsblas::replay_file(&path) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("rsblas-replay: {}: {}", path, e);
+            process::exit(2);
+        }
+    };
+
+    for m in &report.mismatches {
+        println!("call {} {} output {}[{}]: recorded {:e}, replayed {:e}",
+                 m.call, m.routine.to_uppercase(), m.output, m.index, m.expected, m.actual);
+    }
+    for routine in &report.skipped {
+        println!("skipped unknown routine {}", routine);
+    }
+    println!("{} calls replayed, {} compared, {} mismatches", report.calls, report.compared, report.mismatches.len());
+    if !report.is_ok() {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,319 @@
+use std::fmt;
+use std::mem;
+use std::slice;
+use std::ops::{Add, AddAssign, Sub, SubAssign, Neg, Mul, MulAssign, Div, DivAssign};
+use crate::error::BlasError;
+use crate::scalar::{Scalar, RealScalar};
+
+/// Complex number `re + i im`.
+///
+/// The layout is `repr(C)`: `re` then `im`, exactly like C99 `_Complex`, Fortran `COMPLEX` and the interleaved
+/// arrays of FFT libraries, so a slice of `Complex<T>` can be viewed as twice as many `T` and back without
+/// copying, see `from_interleaved` and `as_interleaved`.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[repr(C)]
+pub struct Complex<T> {
+    pub re: T,
+    pub im: T,
+}
+
+pub type Complex32 = Complex<f32>;
+pub type Complex64 = Complex<f64>;
+
+impl<T: RealScalar> Complex<T> {
+    pub fn new(re: T, im: T) -> Self {
+        Self { re, im }
+    }
+
+    /// `r * (cos(theta) + i sin(theta))`.
+    pub fn from_polar(r: T, theta: T) -> Self {
+        Self::new(r * theta.cos(), r * theta.sin())
+    }
+
+    /// Imaginary unit.
+    pub fn i() -> Self {
+        Self::new(T::zero(), T::one())
+    }
+
+    /// Magnitude `|z|`, computed with `hypot` so that it does not overflow when `|z|` is representable.
+    pub fn abs(self) -> T {
+        self.re.hypot(self.im)
+    }
+
+    /// Squared magnitude `re² + im²`, cheaper than `abs` but liable to overflow.
+    pub fn norm_sqr(self) -> T {
+        self.re * self.re + self.im * self.im
+    }
+
+    /// Argument in `[-pi, pi]`.
+    pub fn arg(self) -> T {
+        self.im.atan2(self.re)
+    }
+
+    pub fn conj(self) -> Self {
+        Self::new(self.re, -self.im)
+    }
+
+    /// `1 / z`.
+    pub fn recip(self) -> Self {
+        Self::new(T::one(), T::zero()) / self
+    }
+
+    /// `e^z`.
+    pub fn exp(self) -> Self {
+        Self::from_polar(self.re.exp(), self.im)
+    }
+
+    /// Principal natural logarithm, with the imaginary part in `[-pi, pi]`.
+    pub fn ln(self) -> Self {
+        Self::new(self.abs().ln(), self.arg())
+    }
+
+    /// Principal value of `z^p`, with `0^p = 0` for `p > 0`, `0^0 = 1` and an infinite `0^p` for `p < 0`.
+    pub fn powf(self, p: T) -> Self {
+        if self.re == T::zero() && self.im == T::zero() && p > T::zero() {
+            return self;
+        }
+        Self::from_polar(self.abs().powf(p), self.arg() * p)
+    }
+}
+
+impl<T: RealScalar> Complex<T> {
+    /// Views interleaved `[re0, im0, re1, im1, ...]` data as complex numbers, without copying.
+    ///
+    /// Fails if `data` has an odd length or is not aligned for `Complex<T>`.
+    pub fn from_interleaved(data: &[T]) -> Result<&[Self], BlasError> {
+        check_interleaved(data)?;
+        // SAFETY: `Complex<T>` is `repr(C)` with two `T` fields and no padding, `data` is suitably aligned and
+        // holds exactly `2 * (len / 2)` elements.
+        Ok(unsafe { slice::from_raw_parts(data.as_ptr() as *const Self, data.len() / 2) })
+    }
+
+    /// Mutable counterpart of `from_interleaved`.
+    pub fn from_interleaved_mut(data: &mut [T]) -> Result<&mut [Self], BlasError> {
+        check_interleaved(data)?;
+        // SAFETY: see `from_interleaved`, and the exclusive borrow of `data` moves to the result.
+        Ok(unsafe { slice::from_raw_parts_mut(data.as_mut_ptr() as *mut Self, data.len() / 2) })
+    }
+
+    /// Views complex numbers as interleaved `[re0, im0, re1, im1, ...]` data, without copying.
+    pub fn as_interleaved(z: &[Self]) -> &[T] {
+        // SAFETY: `Complex<T>` is `repr(C)` with two `T` fields and no padding.
+        unsafe { slice::from_raw_parts(z.as_ptr() as *const T, 2 * z.len()) }
+    }
+
+    /// Mutable counterpart of `as_interleaved`.
+    pub fn as_interleaved_mut(z: &mut [Self]) -> &mut [T] {
+        // SAFETY: see `as_interleaved`.
+        unsafe { slice::from_raw_parts_mut(z.as_mut_ptr() as *mut T, 2 * z.len()) }
+    }
+}
+
+fn check_interleaved<T>(data: &[T]) -> Result<(), BlasError> {
+    if data.len() % 2 != 0 {
+        return Err(BlasError::DimensionMismatch { pos: 1, name: "data", expected: data.len() + 1, actual: data.len() });
+    }
+    let align = mem::align_of::<Complex<T>>();
+    if data.as_ptr() as usize % align != 0 {
+        return Err(BlasError::Misaligned { pos: 1, name: "data", align });
+    }
+    Ok(())
+}
+
+impl<T: Add<Output = T>> Add for Complex<T> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self{ re: self.re + other.re, im: self.im + other.im}
+    }
+}
+impl<T: AddAssign> AddAssign for Complex<T> {
+    fn add_assign(&mut self, other: Self) {
+        self.re += other.re;
+        self.im += other.im;
+    }
+}
+impl<T: Sub<Output = T>> Sub for Complex<T> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Self{ re: self.re - other.re, im: self.im - other.im }
+    }
+}
+impl<T: SubAssign> SubAssign for Complex<T> {
+    fn sub_assign(&mut self, other: Self) {
+        self.re -= other.re;
+        self.im -= other.im;
+    }
+}
+impl<T: Neg<Output = T>> Neg for Complex<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self { re: -self.re, im: -self.im}
+    }
+}
+impl<T: Copy + Mul<Output = T> + Sub<Output = T> + Add<Output = T>> Mul for Complex<T> {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        Self{
+            re: self.re * other.re - self.im * other.im,
+            im: self.im * other.re + self.re * other.im,
+        }
+    }
+}
+impl<T: Copy + Mul<Output = T> + Sub<Output = T> + Add<Output = T>> MulAssign for Complex<T> {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+impl<T: RealScalar> Mul<T> for Complex<T> {
+    type Output = Self;
+    fn mul(self, a: T) -> Self {
+        Self::new(self.re * a, self.im * a)
+    }
+}
+impl<T: RealScalar> MulAssign<T> for Complex<T> {
+    fn mul_assign(&mut self, a: T) {
+        *self = *self * a;
+    }
+}
+
+/// Smith's algorithm, dividing by the larger component of the denominator so that no intermediate overflows
+/// needlessly, with Baudin and Smith's fallback for a ratio that underflows to zero.
+impl<T: RealScalar> Div for Complex<T> {
+    type Output = Self;
+    fn div(self, other: Self) -> Self {
+        let (a, b, c, d) = (self.re, self.im, other.re, other.im);
+        if d.abs() <= c.abs() {
+            let r = d / c;
+            let t = T::one() / (c + d * r);
+            if r != T::zero() {
+                Self::new((a + b * r) * t, (b - a * r) * t)
+            } else {
+                Self::new((a + d * (b / c)) * t, (b - d * (a / c)) * t)
+            }
+        } else {
+            let r = c / d;
+            let t = T::one() / (d + c * r);
+            if r != T::zero() {
+                Self::new((a * r + b) * t, (b * r - a) * t)
+            } else {
+                Self::new((c * (a / d) + b) * t, (c * (b / d) - a) * t)
+            }
+        }
+    }
+}
+impl<T: RealScalar> DivAssign for Complex<T> {
+    fn div_assign(&mut self, other: Self) {
+        *self = *self / other;
+    }
+}
+impl<T: RealScalar> Div<T> for Complex<T> {
+    type Output = Self;
+    fn div(self, a: T) -> Self {
+        Self::new(self.re / a, self.im / a)
+    }
+}
+impl<T: RealScalar> DivAssign<T> for Complex<T> {
+    fn div_assign(&mut self, a: T) {
+        *self = *self / a;
+    }
+}
+
+macro_rules! impl_real_times_complex {
+    ($t:ty) => (
+        impl Mul<Complex<$t>> for $t {
+            type Output = Complex<$t>;
+            fn mul(self, z: Complex<$t>) -> Complex<$t> {
+                z * self
+            }
+        }
+    )
+}
+impl_real_times_complex!(f32);
+impl_real_times_complex!(f64);
+
+/// Formats as `re+imi` or `re-imi`, applying the precision to both parts.
+impl<T: RealScalar + fmt::Display> fmt::Display for Complex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.re, f)?;
+        if self.im.is_sign_negative() {
+            f.write_str("-")?;
+            fmt::Display::fmt(&-self.im, f)?;
+        } else {
+            f.write_str("+")?;
+            fmt::Display::fmt(&self.im, f)?;
+        }
+        f.write_str("i")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: Complex64, b: Complex64) -> bool {
+        (a - b).abs() <= 1e-12 * b.abs().max(1.0)
+    }
+
+    #[test]
+    fn arithmetic_matches_known_values() {
+        let z = Complex64::new(3.0, 4.0);
+        let w = Complex64::new(1.0, -2.0);
+        assert_eq!(z.abs(), 5.0);
+        assert_eq!(z.norm_sqr(), 25.0);
+        assert_eq!(z.conj(), Complex64::new(3.0, -4.0));
+        assert_eq!(z * w, Complex64::new(11.0, -2.0));
+        assert_eq!(z / w, Complex64::new(-1.0, 2.0));
+        assert_eq!(z * 2.0, 2.0 * z);
+        assert_eq!(z / 2.0, Complex64::new(1.5, 2.0));
+        assert_eq!(Complex32::new(0.0, 2.0).recip(), Complex32::new(0.0, -0.5));
+        assert_eq!(format!("{}", w), "1-2i");
+        assert_eq!(format!("{:.1}", z), "3.0+4.0i");
+        assert_eq!(format!("{}", Complex::new(1.0_f64, -0.0)), "1-0i");
+    }
+
+    #[test]
+    fn division_and_abs_avoid_spurious_overflow() {
+        let big = Complex64::new(1e300, 1e300);
+        assert_eq!(big / big, Complex64::new(1.0, 0.0));
+        assert_eq!(Complex64::new(1e200, 1e200).abs(), 2.0_f64.sqrt() * 1e200);
+        let tiny = Complex64::new(1e-300, 1e-300);
+        assert!(close(tiny / Complex64::new(1e-300, 0.0), Complex64::new(1.0, 1.0)));
+        let q = Complex64::new(1.0, 1.0) / Complex64::new(1.0, 1e-308);
+        assert!(close(q, Complex64::new(1.0, 1.0)));
+    }
+
+    #[test]
+    fn interleaved_slices_are_reinterpreted_in_place() {
+        let mut data = [1.0_f32, 2.0, 3.0, -4.0];
+        let z = Complex32::from_interleaved(&data).unwrap();
+        assert_eq!(z, [Complex32::new(1.0, 2.0), Complex32::new(3.0, -4.0)]);
+        assert_eq!(Complex::as_interleaved(z).as_ptr(), data.as_ptr());
+
+        Complex32::from_interleaved_mut(&mut data).unwrap()[1] *= Complex32::i();
+        assert_eq!(data, [1.0, 2.0, 4.0, 3.0]);
+        let mut w = [Complex64::new(1.0, 2.0)];
+        Complex::as_interleaved_mut(&mut w)[1] = 5.0;
+        assert_eq!(w[0].im, 5.0);
+
+        assert_eq!(Complex32::from_interleaved(&data[.. 3]),
+                   Err(BlasError::DimensionMismatch { pos: 1, name: "data", expected: 4, actual: 3 }));
+        assert_eq!(mem::size_of::<Complex64>(), 16);
+    }
+
+    #[test]
+    fn transcendental_functions_match_known_values() {
+        use std::f64::consts::{E, FRAC_PI_2, PI};
+        let i = Complex64::i();
+        assert!(close((i * PI).exp(), Complex64::new(-1.0, 0.0)));
+        assert!(close(Complex64::new(1.0, 0.0).exp(), Complex64::new(E, 0.0)));
+        assert!(close(Complex64::new(-1.0, 0.0).ln(), Complex64::new(0.0, PI)));
+        assert_eq!(i.arg(), FRAC_PI_2);
+        assert!(close(Complex64::from_polar(2.0, FRAC_PI_2), Complex64::new(0.0, 2.0)));
+        assert!(close(i.powf(2.0), Complex64::new(-1.0, 0.0)));
+        assert!(close(Complex64::new(3.0, 4.0).powf(0.5), Complex64::new(2.0, 1.0)));
+        assert_eq!(Complex64::default().powf(2.0), Complex64::default());
+        assert_eq!(Complex64::default().powf(0.0), Complex64::new(1.0, 0.0));
+        assert_eq!(Complex64::default().powf(-1.0).re, f64::INFINITY);
+        assert!(Complex64::default().powf(f64::NAN).re.is_nan());
+    }
+}
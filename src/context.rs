@@ -0,0 +1,237 @@
+use std::cell::RefCell;
+use std::sync::{Arc, RwLock};
+use crate::stats::Stats;
+use crate::workspace::ScratchPool;
+
+/// Instruction set a routine may use, from the most portable to the most capable.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Isa {
+    /// plain Rust, no explicit vector instructions
+    Scalar,
+    /// 128-bit SSE2
+    Sse2,
+    /// 256-bit AVX2
+    Avx2,
+    /// AVX2 with fused multiply-add
+    Fma,
+}
+
+impl Isa {
+    /// Most capable instruction set supported by the running CPU.
+    pub fn detect() -> Isa {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if is_x86_feature_detected!("avx2") {
+                return if is_x86_feature_detected!("fma") { Isa::Fma } else { Isa::Avx2 };
+            }
+            if is_x86_feature_detected!("sse2") {
+                return Isa::Sse2;
+            }
+        }
+        Isa::Scalar
+    }
+}
+
+/// Execution settings of the routines: maximum thread count, allowed instruction set, reproducibility, the
+/// scratch memory pool and the statistics of the calls run under it.
+///
+/// Every routine runs under the context in effect on the calling thread: the one installed by
+/// `Context::install` for the duration of a closure, otherwise the process-wide context set by
+/// `Context::set_global`, which defaults to `Context::new()`.
+///
+/// ```
+/// use rsblas::{Context, Isa, daxpy};
+///
+/// let small = Context::new().with_max_threads(1).with_isa(Isa::Scalar);
+/// let mut y = [1.0, 2.0];
+/// small.install(|| daxpy(2, 2.0, &[1.0, 1.0], 1, &mut y, 1)).unwrap();
+/// assert_eq!(y, [3.0, 4.0]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Context {
+    max_threads: usize,
+    isa: Isa,
+    reproducible: bool,
+    parallel_threshold: usize,
+    scratch: Arc<ScratchPool>,
+    stats: Arc<Stats>,
+}
+
+/// default of `Context::with_parallel_threshold`
+const PARALLEL_THRESHOLD: usize = 1 << 16;
+
+static GLOBAL_CONTEXT: RwLock<Option<Context>> = RwLock::new(None);
+
+thread_local! {
+    static THREAD_CONTEXT: RefCell<Option<Context>> = const { RefCell::new(None) };
+}
+
+/// puts the previously installed context back, also when the closure panics
+struct Restore(Option<Context>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let prev = self.0.take();
+        THREAD_CONTEXT.with(|c| *c.borrow_mut() = prev);
+    }
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Context::new()
+    }
+}
+
+impl Context {
+    /// Context using every available core and the best instruction set of the CPU, not reproducible, with a
+    /// fresh scratch pool and statistics.
+    pub fn new() -> Self {
+        Context {
+            max_threads: std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+            isa: Isa::detect(),
+            reproducible: false,
+            parallel_threshold: PARALLEL_THRESHOLD,
+            scratch: Arc::new(ScratchPool::new()),
+            stats: Arc::new(Stats::new()),
+        }
+    }
+
+    /// Limits the number of threads a single call may use, `0` is taken as `1`.
+    pub fn with_max_threads(mut self, max_threads: usize) -> Self {
+        self.max_threads = max_threads.max(1);
+        self
+    }
+
+    /// Restricts the instruction set, a level the CPU does not support falls back to the best one it does.
+    pub fn with_isa(mut self, isa: Isa) -> Self {
+        self.isa = isa.min(Isa::detect());
+        self
+    }
+
+    /// In reproducible mode, reductions such as `dot` and `asum` return bit-identical results for the same
+    /// inputs whatever the thread count and instruction set.
+    pub fn with_reproducible(mut self, reproducible: bool) -> Self {
+        self.reproducible = reproducible;
+        self
+    }
+
+    /// Sets the number of elements each thread of a Level 1 routine works on at least: a call on `n` elements
+    /// uses `min(max_threads, n / parallel_threshold)` threads, one below twice the threshold, and a batch counts
+    /// the elements of all its problems. `0` is taken as `1`.
+    pub fn with_parallel_threshold(mut self, parallel_threshold: usize) -> Self {
+        self.parallel_threshold = parallel_threshold.max(1);
+        self
+    }
+
+    /// Shares `scratch` with other contexts instead of the context's own pool.
+    pub fn with_scratch(mut self, scratch: Arc<ScratchPool>) -> Self {
+        self.scratch = scratch;
+        self
+    }
+
+    /// Counts the calls into `stats` together with other contexts instead of the context's own statistics.
+    pub fn with_stats(mut self, stats: Arc<Stats>) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    pub fn max_threads(&self) -> usize {
+        self.max_threads
+    }
+
+    pub fn isa(&self) -> Isa {
+        self.isa
+    }
+
+    pub fn is_reproducible(&self) -> bool {
+        self.reproducible
+    }
+
+    pub fn parallel_threshold(&self) -> usize {
+        self.parallel_threshold
+    }
+
+    pub fn scratch(&self) -> &Arc<ScratchPool> {
+        &self.scratch
+    }
+
+    /// Counts of the calls run under this context while counting was on, see `set_stats`.
+    pub fn stats(&self) -> &Arc<Stats> {
+        &self.stats
+    }
+
+    /// Runs `f` with `self` as the context of the calling thread, then restores the previous one.
+    pub fn install<R>(&self, f: impl FnOnce() -> R) -> R {
+        let prev = THREAD_CONTEXT.with(|c| c.replace(Some(self.clone())));
+        let _restore = Restore(prev);
+        f()
+    }
+
+    /// Installs the process-wide context and returns the previous one.
+    pub fn set_global(ctx: Context) -> Context {
+        let mut global = GLOBAL_CONTEXT.write().unwrap_or_else(|e| e.into_inner());
+        global.replace(ctx).unwrap_or_default()
+    }
+
+    /// Context in effect on the calling thread.
+    pub fn current() -> Context {
+        if let Some(ctx) = THREAD_CONTEXT.with(|c| c.borrow().clone()) {
+            return ctx;
+        }
+        if let Some(ctx) = GLOBAL_CONTEXT.read().unwrap_or_else(|e| e.into_inner()).clone() {
+            return ctx;
+        }
+        let mut global = GLOBAL_CONTEXT.write().unwrap_or_else(|e| e.into_inner());
+        global.get_or_insert_with(Context::new).clone()
+    }
+}
+
+/// settings of a context the kernels look up on every call
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Settings {
+    pub(crate) isa: Isa,
+    pub(crate) reproducible: bool,
+    pub(crate) max_threads: usize,
+    pub(crate) parallel_threshold: usize,
+}
+
+impl Settings {
+    fn of(ctx: &Context) -> Settings {
+        Settings {
+            isa: ctx.isa,
+            reproducible: ctx.reproducible,
+            max_threads: ctx.max_threads,
+            parallel_threshold: ctx.parallel_threshold,
+        }
+    }
+}
+
+/// settings of the context in effect on the calling thread, without cloning it
+pub(crate) fn current_settings() -> Settings {
+    if let Some(settings) = THREAD_CONTEXT.with(|c| c.borrow().as_ref().map(Settings::of)) {
+        return settings;
+    }
+    if let Some(ctx) = GLOBAL_CONTEXT.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        return Settings::of(ctx);
+    }
+    Settings::of(&Context::current())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn installed_context_is_scoped_to_the_closure() {
+        let ctx = Context::new().with_max_threads(0).with_isa(Isa::Scalar).with_reproducible(true);
+        assert_eq!(ctx.max_threads(), 1);
+        ctx.install(|| {
+            let current = Context::current();
+            assert_eq!((current.max_threads(), current.isa(), current.is_reproducible()), (1, Isa::Scalar, true));
+            Context::new().with_max_threads(3).install(|| assert_eq!(Context::current().max_threads(), 3));
+            assert_eq!(Context::current().max_threads(), 1);
+        });
+        assert!(Arc::ptr_eq(Context::current().scratch(), Context::current().scratch()));
+        assert!(!Arc::ptr_eq(Context::current().scratch(), ctx.scratch()));
+    }
+}
@@ -1,50 +1,217 @@
-use crate::utils::{check_inc, get_first_index};
-
-fn copy<T>(n: isize, x: &[T], incx: isize, y: &mut [T], incy: isize) -> bool
-where T: Copy,
-{
-    if check_inc(n, x, incx) != true || check_inc(n, y, incy) != true {
-        return false;
-    }
-
-    let n_usize = n as usize;
-    if incx == 1 && incy == 1 {
-        for i in 0 .. n_usize {
-            y[i] = x[i];
-        }
-        return true;
-    }
-
-    let incx_abs: usize = if incx > 0 {incx as usize} else {(-incx) as usize};
-    let mut ix: usize = get_first_index(n_usize, incx);
-    
-    let incy_abs: usize = if incy > 0 {incy as usize} else {(-incy) as usize};
-    let mut iy: usize = get_first_index(n_usize, incy);
-
-    for _ in 0 .. n_usize {
-        y[iy] = x[ix];
-        
-        ix = if incx > 0 {
-            ix + incx_abs
-        } else {
-            ix - incx_abs
-        };
-        iy = if incy > 0 {
-            iy + incy_abs
-        } else {
-            iy - incy_abs
-        };
-    }
-
-    true
-}
-
-/// copies a `f32` vector into another `f32` vector
-pub fn scopy(n: isize, x: &[f32], incx: isize, y: &mut [f32], incy: isize) -> bool {
-    copy::<f32>(n, x, incx, y, incy)
-}
-
-/// copies a `f64` vector into another `f64` vector
-pub fn dcopy(n: isize, x: &[f64], incx: isize, y: &mut [f64], incy: isize) -> bool {
-    copy::<f64>(n, x, incx, y, incy)
-}
\ No newline at end of file
+use crate::context::Context;
+use crate::error::BlasError;
+use crate::xerbla::report;
+use crate::trace::{self, Arg};
+use crate::view::{VecRef, VecMut, check_len, check_within};
+use crate::utils::{check_n, check_inc};
+use crate::strided::walk2;
+use crate::dispatch;
+use crate::parallel::{self, chunk};
+
+/// copies a vector into another vector, for any `Copy` element type
+///
+/// The copy runs on the calling thread; `scopy` and `dcopy` split large copies over the context's threads.
+pub fn copy<T>(n: isize, x: &[T], incx: isize, y: &mut [T], incy: isize) -> Result<(), BlasError>
+where T: Copy,
+{
+    let n_usize = check_copy(n, x, incx, y, incy)?;
+    // SAFETY: both slices were checked to hold `n` elements at their increments, and `&mut y` cannot alias `x`.
+    unsafe { copy_unchecked(n_usize, x.as_ptr(), incx, y.as_mut_ptr(), incy) };
+    Ok(())
+}
+
+fn check_copy<T>(n: isize, x: &[T], incx: isize, y: &[T], incy: isize) -> Result<usize, BlasError> {
+    let n_usize = check_n(n, 1)?;
+    check_inc(n_usize, x, incx, 2, "x", "incx")?;
+    check_inc(n_usize, y, incy, 4, "y", "incy")?;
+    Ok(n_usize)
+}
+
+/// `copy` on raw pointers, validated by debug assertions only, like CBLAS `cblas_?copy`
+///
+/// As in BLAS, a negative increment walks the array from its end: element `i` of `x` is `x[(n - 1 - i) * |incx|]`.
+///
+/// # Safety
+///
+/// `x` must be valid for reads of `(n - 1) * |incx| + 1` elements, `y` for writes of `(n - 1) * |incy| + 1`
+/// elements, and the two ranges must not overlap.
+pub unsafe fn copy_unchecked<T>(n: usize, x: *const T, incx: isize, y: *mut T, incy: isize)
+where T: Copy,
+{
+    debug_assert!(n == 0 || (!x.is_null() && !y.is_null()));
+    debug_assert!(n <= 1 || incy != 0, "incy must be nonzero");
+    if n == 0 {
+        return;
+    }
+
+    if incx == 1 && incy == 1 {
+        dispatch::copy_contiguous(n, x, y);
+        return;
+    }
+
+    walk2(n, x as *mut T, incx, y, incy, |px, py| *py = *px);
+}
+
+/// `copy` split over the context's threads when `n` is large enough, for element types that may cross threads
+fn copy_sync<T>(n: isize, x: &[T], incx: isize, y: &mut [T], incy: isize) -> Result<(), BlasError>
+where T: Copy + Send + Sync,
+{
+    let n_usize = check_copy(n, x, incx, y, incy)?;
+    // SAFETY: both slices were checked to hold `n` elements at their increments, and `&mut y` cannot alias `x`.
+    unsafe { copy_split(n_usize, x.as_ptr(), incx, y.as_mut_ptr(), incy) };
+    Ok(())
+}
+
+/// `copy_unchecked` split over the context's threads when `n` is large enough
+unsafe fn copy_split<T>(n: usize, x: *const T, incx: isize, y: *mut T, incy: isize)
+where T: Copy + Send + Sync,
+{
+    let x = x as *mut T;
+    // SAFETY: the chunks write disjoint parts of `y`, and `T` is `Send + Sync`.
+    let done = parallel::split(n, |lo, hi| {
+        copy_unchecked(hi - lo, chunk(x, n, incx, lo, hi), incx, chunk(y, n, incy, lo, hi), incy)
+    });
+    if done.is_none() {
+        copy_unchecked(n, x, incx, y, incy);
+    }
+}
+
+/// copies a `f32` vector into another `f32` vector
+pub fn scopy(n: isize, x: &[f32], incx: isize, y: &mut [f32], incy: isize) -> Result<(), BlasError> {
+    let call = trace::begin("scopy", &[
+        ("n", Arg::Int(n)), ("x", Arg::F32s(x)), ("incx", Arg::Int(incx)), ("y", Arg::F32s(y)),
+        ("incy", Arg::Int(incy)),
+    ]);
+    let result = copy_sync(n, x, incx, y, incy).map_err(|e| report("scopy", e));
+    trace::end(call, result.is_ok(), &[("y", Arg::F32s(y))]);
+    result
+}
+
+/// copies a `f64` vector into another `f64` vector
+pub fn dcopy(n: isize, x: &[f64], incx: isize, y: &mut [f64], incy: isize) -> Result<(), BlasError> {
+    let call = trace::begin("dcopy", &[
+        ("n", Arg::Int(n)), ("x", Arg::F64s(x)), ("incx", Arg::Int(incx)), ("y", Arg::F64s(y)),
+        ("incy", Arg::Int(incy)),
+    ]);
+    let result = copy_sync(n, x, incx, y, incy).map_err(|e| report("dcopy", e));
+    trace::end(call, result.is_ok(), &[("y", Arg::F64s(y))]);
+    result
+}
+
+/// `scopy` on raw pointers without bounds checks
+///
+/// # Safety
+///
+/// See `copy_unchecked`.
+pub unsafe fn scopy_unchecked(n: usize, x: *const f32, incx: isize, y: *mut f32, incy: isize) {
+    copy_split::<f32>(n, x, incx, y, incy)
+}
+
+/// `dcopy` on raw pointers without bounds checks
+///
+/// # Safety
+///
+/// See `copy_unchecked`.
+pub unsafe fn dcopy_unchecked(n: usize, x: *const f64, incx: isize, y: *mut f64, incy: isize) {
+    copy_split::<f64>(n, x, incx, y, incy)
+}
+
+/// copies a vector view into another, for any `Copy` element type
+pub fn copy_view<T>(x: VecRef<'_, T>, y: &mut VecMut<'_, T>) -> Result<(), BlasError>
+where T: Copy,
+{
+    check_len(x.len(), y.len(), 2, "y")?;
+    let (n, x, incx) = x.as_blas();
+    let (_, y, incy) = y.as_blas();
+    copy(n, x, incx, y, incy)
+}
+
+/// `copy_view` split over the context's threads when the views are long enough
+fn copy_view_split<T>(x: VecRef<'_, T>, y: &mut VecMut<'_, T>) -> Result<(), BlasError>
+where T: Copy + Send + Sync,
+{
+    check_len(x.len(), y.len(), 2, "y")?;
+    let (n, x, incx) = x.as_blas();
+    let (_, y, incy) = y.as_blas();
+    copy_sync(n, x, incx, y, incy)
+}
+
+/// `scopy` on vector views
+pub fn scopy_view(x: VecRef<'_, f32>, y: &mut VecMut<'_, f32>) -> Result<(), BlasError> {
+    copy_view_split(x, y).map_err(|e| report("scopy_view", e))
+}
+
+/// `dcopy` on vector views
+pub fn dcopy_view(x: VecRef<'_, f64>, y: &mut VecMut<'_, f64>) -> Result<(), BlasError> {
+    copy_view_split(x, y).map_err(|e| report("dcopy_view", e))
+}
+
+/// copies a vector of a buffer into another vector of the same buffer, `x[i] = buf[offx + i * incx]` and
+/// `y[i] = buf[offy + i * incy]`, for any `Copy` element type
+///
+/// Like `memmove`, `x` and `y` may overlap: the result is as if `x` had first been copied to a temporary.
+/// Overlapping vectors with the same stride are copied in place in the safe direction, other overlapping
+/// layouts go through a temporary taken from the scratch pool of the current `Context`.
+pub fn copy_within<T>(n: isize, buf: &mut [T], offx: usize, incx: isize, offy: usize, incy: isize) -> Result<(), BlasError>
+where T: Copy,
+{
+    let (x, y) = check_within(n, buf.len(), offx, incx, offy, incy)?;
+    if !x.overlaps(&y) {
+        let base = buf.as_mut_ptr();
+        // SAFETY: `check_within` kept both vectors inside `buf`, and they do not share an element.
+        unsafe { copy_unchecked(x.len, base.add(x.bounds().0), x.stride, base.add(y.bounds().0), y.stride) };
+    } else if x.stride == y.stride {
+        // walking forward is safe as long as every write lands on an element that was already read
+        let forward = (y.offset as isize - x.offset as isize) * x.stride.signum() <= 0;
+        if forward {
+            for i in 0 .. x.len {
+                buf[y.index(i)] = buf[x.index(i)];
+            }
+        } else {
+            for i in (0 .. x.len).rev() {
+                buf[y.index(i)] = buf[x.index(i)];
+            }
+        }
+    } else {
+        Context::current().scratch().with_scratch(x.len, buf[x.offset], |temp| {
+            for (i, t) in temp.iter_mut().enumerate() {
+                *t = buf[x.index(i)];
+            }
+            for (i, &t) in temp.iter().enumerate() {
+                buf[y.index(i)] = t;
+            }
+        });
+    }
+    Ok(())
+}
+
+/// `scopy` within one buffer with `memmove` semantics, see `copy_within`
+pub fn scopy_within(n: isize, buf: &mut [f32], offx: usize, incx: isize, offy: usize, incy: isize) -> Result<(), BlasError> {
+    copy_within::<f32>(n, buf, offx, incx, offy, incy).map_err(|e| report("scopy_within", e))
+}
+
+/// `dcopy` within one buffer with `memmove` semantics, see `copy_within`
+pub fn dcopy_within(n: isize, buf: &mut [f64], offx: usize, incx: isize, offy: usize, incy: isize) -> Result<(), BlasError> {
+    copy_within::<f64>(n, buf, offx, incx, offy, incy).map_err(|e| report("dcopy_within", e))
+}
+
+/// scratch bytes `copy_within` borrows from the context's pool for these arguments, `0` when the vectors do not
+/// overlap, have the same stride or are rejected; reserving them beforehand with `ScratchPool::reserve` keeps
+/// the call from allocating
+pub fn copy_within_workspace<T>(n: isize, offx: usize, incx: isize, offy: usize, incy: isize) -> usize {
+    // no slice is longer than `isize::MAX` elements, the call itself checks the actual length of `buf`
+    match check_within(n, isize::MAX as usize, offx, incx, offy, incy) {
+        Ok((x, y)) if x.overlaps(&y) && x.stride != y.stride => x.len * std::mem::size_of::<T>(),
+        _ => 0,
+    }
+}
+
+/// scratch bytes of `scopy_within`, see `copy_within_workspace`
+pub fn scopy_within_workspace(n: isize, offx: usize, incx: isize, offy: usize, incy: isize) -> usize {
+    copy_within_workspace::<f32>(n, offx, incx, offy, incy)
+}
+
+/// scratch bytes of `dcopy_within`, see `copy_within_workspace`
+pub fn dcopy_within_workspace(n: isize, offx: usize, incx: isize, offy: usize, incy: isize) -> usize {
+    copy_within_workspace::<f64>(n, offx, incx, offy, incy)
+}
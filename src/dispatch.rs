@@ -0,0 +1,172 @@
+use std::any::TypeId;
+use std::mem;
+use crate::context::{self, Isa};
+use crate::scalar::Scalar;
+
+/// Vectorised kernels of an element type on vectors of `n` elements, contiguous or strided, picked at run time
+/// from the instruction set of the context in effect.
+///
+/// Results may differ from the generic loops: `axpy` and `rot` by the rounding a fused multiply-add saves, at
+/// most 1 ulp of each element, `asum` and `dot` by the summation order, within `n * eps * sum(|x_i * y_i|)`. The
+/// reductions are `None` in a reproducible context, since their summation order depends on the vector width.
+/// Tables only exist for `f32` and `f64`.
+#[derive(Clone, Copy)]
+pub(crate) struct Kernels<T: Scalar> {
+    pub(crate) asum: Option<unsafe fn(usize, *const T) -> T::Real>,
+    pub(crate) axpy: unsafe fn(usize, T, *const T, *mut T),
+    pub(crate) dot: Option<unsafe fn(usize, *const T, *const T) -> T>,
+    pub(crate) rot: unsafe fn(usize, *mut T, *mut T, T, T),
+    pub(crate) scal: unsafe fn(usize, T, *mut T),
+    /// strided variants, taking BLAS increments, `None` when the backend gains nothing on them
+    pub(crate) asum_strided: Option<AsumStrided<T>>,
+    pub(crate) axpy_strided: Option<AxpyStrided<T>>,
+    pub(crate) dot_strided: Option<DotStrided<T>>,
+    pub(crate) scal_strided: Option<ScalStrided<T>>,
+    /// whether the context asks for reproducible reductions, which then stay on one thread
+    pub(crate) reproducible: bool,
+}
+
+type AsumStrided<T> = unsafe fn(usize, *const T, isize) -> <T as Scalar>::Real;
+type AxpyStrided<T> = unsafe fn(usize, T, *const T, isize, *mut T, isize);
+type DotStrided<T> = unsafe fn(usize, *const T, isize, *const T, isize) -> T;
+type ScalStrided<T> = unsafe fn(usize, T, *mut T, isize);
+
+/// kernels of the best backend the context allows among the portable one, `sse2` and `avx2`
+fn select<T: Scalar>(portable: Kernels<T>, sse2: Kernels<T>, avx2: Kernels<T>) -> Kernels<T> {
+    let settings = context::current_settings();
+    let kernels = match settings.isa {
+        Isa::Scalar => portable,
+        Isa::Sse2 | Isa::Avx2 => sse2,
+        Isa::Fma => avx2,
+    };
+    if settings.reproducible {
+        return Kernels { asum: None, dot: None, asum_strided: None, dot_strided: None, reproducible: true, ..kernels };
+    }
+    kernels
+}
+
+/// kernels of `T` for the context in effect, `None` for element types without a table, which use the generic loops
+pub(crate) fn kernels<T: Scalar>() -> Option<Kernels<T>> {
+    let id = TypeId::of::<T>();
+    if id == TypeId::of::<f32>() {
+        // SAFETY: `T` is `f32`.
+        f32_kernels().map(|k| unsafe { mem::transmute_copy::<Kernels<f32>, Kernels<T>>(&k) })
+    } else if id == TypeId::of::<f64>() {
+        // SAFETY: `T` is `f64`.
+        f64_kernels().map(|k| unsafe { mem::transmute_copy::<Kernels<f64>, Kernels<T>>(&k) })
+    } else {
+        None
+    }
+}
+
+fn f32_kernels() -> Option<Kernels<f32>> {
+    use crate::kernels::*;
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    return Some(select(PORTABLE_F32, SSE2_F32, AVX2_F32));
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+    Some(select(PORTABLE_F32, PORTABLE_F32, PORTABLE_F32))
+}
+
+fn f64_kernels() -> Option<Kernels<f64>> {
+    use crate::kernels::*;
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    return Some(select(PORTABLE_F64, SSE2_F64, AVX2_F64));
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+    Some(select(PORTABLE_F64, PORTABLE_F64, PORTABLE_F64))
+}
+
+/// copies `n` elements between non-overlapping ranges, with AVX2 when the context allows it
+///
+/// # Safety
+///
+/// See `ptr::copy_nonoverlapping`.
+pub(crate) unsafe fn copy_contiguous<T>(n: usize, x: *const T, y: *mut T) {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if context::current_settings().isa >= Isa::Avx2 {
+        return crate::simd::copy_bytes(n * std::mem::size_of::<T>(), x as *const u8, y as *mut u8);
+    }
+    std::ptr::copy_nonoverlapping(x, y, n);
+}
+
+/// exchanges `n` elements between non-overlapping ranges, with AVX2 when the context allows it
+///
+/// # Safety
+///
+/// See `ptr::swap_nonoverlapping`.
+pub(crate) unsafe fn swap_contiguous<T>(n: usize, x: *mut T, y: *mut T) {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if context::current_settings().isa >= Isa::Avx2 {
+        return crate::simd::swap_bytes(n * std::mem::size_of::<T>(), x as *mut u8, y as *mut u8);
+    }
+    std::ptr::swap_nonoverlapping(x, y, n);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Context, Isa};
+
+    /// runs `f` with the generic loops and with the best kernels of the CPU
+    fn both<R>(f: impl Fn() -> R) -> (R, R) {
+        (Context::new().with_isa(Isa::Scalar).install(&f), Context::new().install(&f))
+    }
+
+    fn close(a: f64, b: f64, tol: f64) -> bool {
+        (a - b).abs() <= tol
+    }
+
+    #[test]
+    fn kernels_match_the_generic_loops() {
+        let n = 103;
+        let x: Vec<f64> = (0 .. n).map(|i| ((i * 37 % 101) as f64 - 50.0) / 7.0).collect();
+        let y: Vec<f64> = (0 .. n).map(|i| ((i * 53 % 97) as f64 - 48.0) / 3.0).collect();
+        let xs: Vec<f32> = x.iter().map(|&v| v as f32).collect();
+        let bound = n as f64 * x.iter().zip(&y).map(|(a, b)| (a * b).abs()).sum::<f64>();
+
+        let (d0, d1) = both(|| crate::ddot(n as isize, &x, 1, &y, 1).unwrap());
+        assert!(close(d0, d1, bound * f64::EPSILON));
+        let (a0, a1) = both(|| crate::sasum(n as isize, &xs, 1).unwrap());
+        assert!(close(a0 as f64, a1 as f64, n as f64 * a0 as f64 * f32::EPSILON as f64));
+
+        let (y0, y1) = both(|| {
+            let mut y = y.clone();
+            crate::daxpy(n as isize, 0.3, &x, 1, &mut y, 1).unwrap();
+            y
+        });
+        assert!(y0.iter().zip(&y1).all(|(a, b)| close(*a, *b, 2.0 * a.abs().max(1.0) * f64::EPSILON)));
+
+        let (r0, r1) = both(|| {
+            let (mut x, mut y) = (xs.clone(), y.iter().map(|&v| v as f32).collect::<Vec<_>>());
+            crate::srot(n as isize, &mut x, 1, &mut y, 1, 0.6, 0.8).unwrap();
+            (x, y)
+        });
+        let ulps = |a: &[f32], b: &[f32]| {
+            a.iter().zip(b).all(|(a, b)| close(*a as f64, *b as f64, 4.0 * a.abs().max(1.0) as f64 * f32::EPSILON as f64))
+        };
+        assert!(ulps(&r0.0, &r1.0) && ulps(&r0.1, &r1.1));
+
+        let (s0, s1) = both(|| {
+            let mut x = x.clone();
+            crate::dscal(n as isize, -1.5, &mut x, 1).unwrap();
+            x
+        });
+        assert_eq!(s0, s1);
+
+        let (c0, c1) = both(|| {
+            let (mut a, mut b) = (xs.clone(), vec![0.0_f32; n]);
+            crate::sswap(n as isize, &mut a, 1, &mut b, 1).unwrap();
+            let mut c = vec![0.0_f32; n];
+            crate::scopy(n as isize, &b, 1, &mut c, 1).unwrap();
+            (a, c)
+        });
+        assert_eq!(c0, c1);
+        assert_eq!(c1.1, xs);
+    }
+
+    #[test]
+    fn reproducible_reductions_skip_the_kernels() {
+        let x: Vec<f64> = (0 .. 50).map(|i| 1.0 / (i as f64 + 1.0)).collect();
+        let scalar = Context::new().with_isa(Isa::Scalar).install(|| crate::ddot(50, &x, 1, &x, 1)).unwrap();
+        let reproducible = Context::new().with_reproducible(true).install(|| crate::ddot(50, &x, 1, &x, 1)).unwrap();
+        assert_eq!(scalar.to_bits(), reproducible.to_bits());
+    }
+}
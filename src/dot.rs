@@ -1,50 +1,122 @@
-use std::ops::{Add, Mul, AddAssign};
-use crate::utils::{check_inc, get_first_index};
-
-fn dot<T>(n: isize, x: &[T], incx: isize, y: &[T], incy: isize) -> T
-where T: Default + Mul<Output = T> + AddAssign + Copy,
-{
-    if n <= 0 {
-        return <T as Default>::default();
-    }
-
-    if check_inc(n, x, incx) != true || check_inc(n, y, incy) != true {
-        return <T as Default>::default();
-    }
-
-    let mut result: T = <T as Default>::default();
-
-    let n_usize = n as usize;
-    if incx == 1 && incy == 1 {
-        for i in 0 .. n_usize {
-            result += x[i] * y[i];
-        }
-        return result;
-    }
-
-    let incx_abs = if incx > 0 {incx as usize} else {(-incx) as usize};
-    let mut ix: usize = get_first_index(n_usize, incx);
-
-    let incy_abs = if incy > 0 {incy as usize} else {(-incy) as usize};
-    let mut iy: usize = get_first_index(n_usize, incy);
-
-    for _ in 0 .. n_usize {
-        result += x[ix] * y[iy];
-
-        ix = if incx > 0 {ix + incx_abs} else {ix - incx_abs};
-        iy = if incy > 0 {iy + incy_abs} else {iy - incy_abs};
-    }
-
-    result
-
-}
-
-/// computes a dot product (inner product) of two `f32` vectors
-pub fn sdot(n: isize, x: &[f32], incx: isize, y: &[f32], incy: isize) -> f32 {
-    dot::<f32>(n, x, incx, y, incy)
-}
-
-/// computes a dot product (inner product) of two `f64` vectors
-pub fn ddot(n: isize, x: &[f64], incx: isize, y: &[f64], incy: isize) -> f64 {
-    dot::<f64>(n, x, incx, y, incy)
-}
\ No newline at end of file
+use crate::error::BlasError;
+use crate::scalar::Scalar;
+use crate::xerbla::report;
+use crate::trace::{self, Arg};
+use crate::view::{VecRef, check_len};
+use crate::utils::{check_n, check_inc};
+use crate::strided::walk2;
+use crate::dispatch::{self, Kernels};
+use crate::parallel::{self, chunk};
+
+/// computes the unconjugated dot product `sum(x[i] * y[i])` of two vectors, for any `Scalar` type
+pub fn dot<T>(n: isize, x: &[T], incx: isize, y: &[T], incy: isize) -> Result<T, BlasError>
+where T: Scalar,
+{
+    let n_usize = check_n(n, 1)?;
+    check_inc(n_usize, x, incx, 2, "x", "incx")?;
+    check_inc(n_usize, y, incy, 4, "y", "incy")?;
+
+    // SAFETY: both slices were checked to hold `n` elements at their increments.
+    Ok(unsafe { dot_unchecked(n_usize, x.as_ptr(), incx, y.as_ptr(), incy) })
+}
+
+/// `dot` on raw pointers, validated by debug assertions only, like CBLAS `cblas_?dot`
+///
+/// As in BLAS, a negative increment walks the array from its end: element `i` of `x` is `x[(n - 1 - i) * |incx|]`.
+///
+/// # Safety
+///
+/// `x` must be valid for reads of `(n - 1) * |incx| + 1` elements and `y` of `(n - 1) * |incy| + 1` elements.
+pub unsafe fn dot_unchecked<T>(n: usize, x: *const T, incx: isize, y: *const T, incy: isize) -> T
+where T: Scalar,
+{
+    debug_assert!(n == 0 || (!x.is_null() && !y.is_null()));
+    let kernels = dispatch::kernels::<T>();
+    if kernels.is_some_and(|k| !k.reproducible) {
+        let (x, y) = (x as *mut T, y as *mut T);
+        // SAFETY: the chunks only read, and kernels, hence `T`, are `f32` or `f64`.
+        let partials = parallel::split(n, |lo, hi| {
+            dot_serial(kernels, hi - lo, chunk(x, n, incx, lo, hi), incx, chunk(y, n, incy, lo, hi), incy)
+        });
+        if let Some(partials) = partials {
+            return partials.into_iter().fold(T::zero(), |sum, p| sum + p);
+        }
+    }
+    dot_serial(kernels, n, x, incx, y, incy)
+}
+
+/// `dot_unchecked` on the calling thread
+unsafe fn dot_serial<T>(kernels: Option<Kernels<T>>, n: usize, x: *const T, incx: isize, y: *const T, incy: isize) -> T
+where T: Scalar,
+{
+    if let Some(kernels) = kernels {
+        match (kernels.dot, kernels.dot_strided) {
+            (Some(dot), _) if incx == 1 && incy == 1 => return dot(n, x, y),
+            (_, Some(dot)) if incx != 1 || incy != 1 => return dot(n, x, incx, y, incy),
+            _ => {}
+        }
+    }
+    let mut result = T::zero();
+    walk2(n, x as *mut T, incx, y as *mut T, incy, |px, py| result += *px * *py);
+    result
+}
+
+/// computes a dot product (inner product) of two `f32` vectors
+pub fn sdot(n: isize, x: &[f32], incx: isize, y: &[f32], incy: isize) -> Result<f32, BlasError> {
+    let call = trace::begin("sdot", &[
+        ("n", Arg::Int(n)), ("x", Arg::F32s(x)), ("incx", Arg::Int(incx)), ("y", Arg::F32s(y)),
+        ("incy", Arg::Int(incy)),
+    ]);
+    let result = dot::<f32>(n, x, incx, y, incy).map_err(|e| report("sdot", e));
+    trace::end(call, result.is_ok(), &[("result", Arg::F32(result.unwrap_or_default()))]);
+    result
+}
+
+/// computes a dot product (inner product) of two `f64` vectors
+pub fn ddot(n: isize, x: &[f64], incx: isize, y: &[f64], incy: isize) -> Result<f64, BlasError> {
+    let call = trace::begin("ddot", &[
+        ("n", Arg::Int(n)), ("x", Arg::F64s(x)), ("incx", Arg::Int(incx)), ("y", Arg::F64s(y)),
+        ("incy", Arg::Int(incy)),
+    ]);
+    let result = dot::<f64>(n, x, incx, y, incy).map_err(|e| report("ddot", e));
+    trace::end(call, result.is_ok(), &[("result", Arg::F64(result.unwrap_or_default()))]);
+    result
+}
+
+/// `sdot` on raw pointers without bounds checks
+///
+/// # Safety
+///
+/// See `dot_unchecked`.
+pub unsafe fn sdot_unchecked(n: usize, x: *const f32, incx: isize, y: *const f32, incy: isize) -> f32 {
+    dot_unchecked::<f32>(n, x, incx, y, incy)
+}
+
+/// `ddot` on raw pointers without bounds checks
+///
+/// # Safety
+///
+/// See `dot_unchecked`.
+pub unsafe fn ddot_unchecked(n: usize, x: *const f64, incx: isize, y: *const f64, incy: isize) -> f64 {
+    dot_unchecked::<f64>(n, x, incx, y, incy)
+}
+
+/// computes the unconjugated dot product of two vector views, for any `Scalar` type
+pub fn dot_view<T>(x: VecRef<'_, T>, y: VecRef<'_, T>) -> Result<T, BlasError>
+where T: Scalar,
+{
+    check_len(x.len(), y.len(), 2, "y")?;
+    let (n, x, incx) = x.as_blas();
+    let (_, y, incy) = y.as_blas();
+    dot(n, x, incx, y, incy)
+}
+
+/// `sdot` on vector views
+pub fn sdot_view(x: VecRef<'_, f32>, y: VecRef<'_, f32>) -> Result<f32, BlasError> {
+    dot_view::<f32>(x, y).map_err(|e| report("sdot_view", e))
+}
+
+/// `ddot` on vector views
+pub fn ddot_view(x: VecRef<'_, f64>, y: VecRef<'_, f64>) -> Result<f64, BlasError> {
+    dot_view::<f64>(x, y).map_err(|e| report("ddot_view", e))
+}
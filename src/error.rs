@@ -0,0 +1,81 @@
+use std::fmt;
+
+/// Reason a routine rejected its arguments.
+///
+/// Every variant names the offending argument by its 1-based position in the routine's parameter list, the
+/// same number reference BLAS reports as `INFO` through `XERBLA`, together with the parameter name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlasError {
+    /// A dimension such as `n` is negative.
+    InvalidDimension { pos: usize, name: &'static str, value: isize },
+    /// An increment such as `incx` is zero, or negative for a routine that only walks forward.
+    InvalidIncrement { pos: usize, name: &'static str, value: isize },
+    /// A vector holds fewer elements than its dimension and increment require.
+    SliceTooShort { pos: usize, name: &'static str, required: usize, actual: usize },
+    /// An offset places the first element of a view too close to the start of its slice for a negative stride.
+    InvalidOffset { pos: usize, name: &'static str, value: usize, required: usize },
+    /// A vector's length differs from the length implied by the other arguments.
+    DimensionMismatch { pos: usize, name: &'static str, expected: usize, actual: usize },
+    /// Two vectors stored in the same buffer share an element where the routine needs them disjoint.
+    Overlap { pos: usize, name: &'static str },
+    /// A buffer does not start on the address boundary its reinterpretation requires.
+    Misaligned { pos: usize, name: &'static str, align: usize },
+}
+
+impl BlasError {
+    /// 1-based position of the offending argument, as reported by reference BLAS `INFO`.
+    pub fn pos(&self) -> usize {
+        match *self {
+            BlasError::InvalidDimension { pos, .. } => pos,
+            BlasError::InvalidIncrement { pos, .. } => pos,
+            BlasError::SliceTooShort { pos, .. } => pos,
+            BlasError::InvalidOffset { pos, .. } => pos,
+            BlasError::DimensionMismatch { pos, .. } => pos,
+            BlasError::Overlap { pos, .. } => pos,
+            BlasError::Misaligned { pos, .. } => pos,
+        }
+    }
+
+    /// Name of the offending argument.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            BlasError::InvalidDimension { name, .. } => name,
+            BlasError::InvalidIncrement { name, .. } => name,
+            BlasError::SliceTooShort { name, .. } => name,
+            BlasError::InvalidOffset { name, .. } => name,
+            BlasError::DimensionMismatch { name, .. } => name,
+            BlasError::Overlap { name, .. } => name,
+            BlasError::Misaligned { name, .. } => name,
+        }
+    }
+}
+
+impl fmt::Display for BlasError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            BlasError::InvalidDimension { pos, name, value } => {
+                write!(f, "parameter {} ({}) had an illegal value {}", pos, name, value)
+            }
+            BlasError::InvalidIncrement { pos, name, value } => {
+                write!(f, "parameter {} ({}) had an illegal increment {}", pos, name, value)
+            }
+            BlasError::SliceTooShort { pos, name, required, actual } => {
+                write!(f, "parameter {} ({}) has {} elements, at least {} required", pos, name, actual, required)
+            }
+            BlasError::InvalidOffset { pos, name, value, required } => {
+                write!(f, "parameter {} ({}) is {}, at least {} required", pos, name, value, required)
+            }
+            BlasError::DimensionMismatch { pos, name, expected, actual } => {
+                write!(f, "parameter {} ({}) has length {}, expected {}", pos, name, actual, expected)
+            }
+            BlasError::Overlap { pos, name } => {
+                write!(f, "parameter {} ({}) makes two vectors share an element", pos, name)
+            }
+            BlasError::Misaligned { pos, name, align } => {
+                write!(f, "parameter {} ({}) is not aligned to {} bytes", pos, name, align)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BlasError {}
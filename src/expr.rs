@@ -0,0 +1,234 @@
+use std::ops::{Add, Sub, Mul, Neg, AddAssign, SubAssign, MulAssign};
+use crate::axpy::axpy_view;
+use crate::dot::dot_view;
+use crate::scal::scal_view;
+use crate::scalar::Scalar;
+use crate::vector::Vector;
+use crate::view::{VecRef, VecMut};
+use crate::Complex;
+
+/// `alpha * x`, built by `alpha * &x` and consumed by `y += alpha * &x` without a temporary vector.
+#[derive(Debug, Clone, Copy)]
+pub struct Scaled<'a, T> {
+    pub alpha: T,
+    pub x: VecRef<'a, T>,
+}
+
+/// `a * x + b * y`, built by adding two `Scaled` terms and evaluated in a single pass.
+#[derive(Debug, Clone, Copy)]
+pub struct LinComb<'a, T> {
+    pub a: Scaled<'a, T>,
+    pub b: Scaled<'a, T>,
+}
+
+fn expect_len(expected: usize, actual: usize) {
+    assert!(expected == actual, "vector length mismatch: {} vs {}", expected, actual);
+}
+
+/// `z := a * x + b * y` or `z := a * x + b * y + z`, one pass over the three vectors
+fn lincomb<T: Scalar>(z: &mut VecMut<'_, T>, e: LinComb<'_, T>, accumulate: bool) {
+    expect_len(e.a.x.len(), e.b.x.len());
+    expect_len(e.a.x.len(), z.len());
+    let (a, b) = (e.a.alpha, e.b.alpha);
+    for ((zi, &xi), &yi) in z.iter_mut().zip(e.a.x.iter()).zip(e.b.x.iter()) {
+        *zi = if accumulate { a * xi + b * yi + *zi } else { a * xi + b * yi };
+    }
+}
+
+impl<'a, T: Scalar> Scaled<'a, T> {
+    pub fn new(alpha: T, x: VecRef<'a, T>) -> Self {
+        Scaled { alpha, x }
+    }
+}
+
+impl<'a, T: Scalar> Neg for Scaled<'a, T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Scaled { alpha: -self.alpha, x: self.x }
+    }
+}
+
+impl<'a, T: Scalar> Add for Scaled<'a, T> {
+    type Output = LinComb<'a, T>;
+    fn add(self, other: Self) -> LinComb<'a, T> {
+        LinComb { a: self, b: other }
+    }
+}
+
+impl<'a, T: Scalar> Sub for Scaled<'a, T> {
+    type Output = LinComb<'a, T>;
+    fn sub(self, other: Self) -> LinComb<'a, T> {
+        LinComb { a: self, b: -other }
+    }
+}
+
+impl<'a, T: Scalar> Mul<T> for &'a Vector<T> {
+    type Output = Scaled<'a, T>;
+    fn mul(self, alpha: T) -> Scaled<'a, T> {
+        Scaled { alpha, x: self.as_view() }
+    }
+}
+
+impl<'a, T: Scalar> Mul<T> for VecRef<'a, T> {
+    type Output = Scaled<'a, T>;
+    fn mul(self, alpha: T) -> Scaled<'a, T> {
+        Scaled { alpha, x: self }
+    }
+}
+
+macro_rules! impl_left_scale {
+    ($t:ty) => (
+        impl<'a> Mul<&'a Vector<$t>> for $t {
+            type Output = Scaled<'a, $t>;
+            fn mul(self, x: &'a Vector<$t>) -> Scaled<'a, $t> {
+                Scaled { alpha: self, x: x.as_view() }
+            }
+        }
+
+        impl<'a> Mul<VecRef<'a, $t>> for $t {
+            type Output = Scaled<'a, $t>;
+            fn mul(self, x: VecRef<'a, $t>) -> Scaled<'a, $t> {
+                Scaled { alpha: self, x }
+            }
+        }
+    )
+}
+impl_left_scale!(f32);
+impl_left_scale!(f64);
+impl_left_scale!(Complex<f32>);
+impl_left_scale!(Complex<f64>);
+
+macro_rules! impl_assign_ops {
+    ($target:ty, $view:ident, $($lt:lifetime)?) => (
+        impl<'a, $($lt,)? T: Scalar> AddAssign<Scaled<'a, T>> for $target {
+            /// `y += a * x`, dispatched to `axpy`. Panics if the lengths differ.
+            fn add_assign(&mut self, e: Scaled<'a, T>) {
+                expect_len(e.x.len(), self.len());
+                axpy_view(e.alpha, e.x, &mut $view(self)).unwrap();
+            }
+        }
+
+        impl<'a, $($lt,)? T: Scalar> SubAssign<Scaled<'a, T>> for $target {
+            /// `y -= a * x`, dispatched to `axpy`. Panics if the lengths differ.
+            fn sub_assign(&mut self, e: Scaled<'a, T>) {
+                *self += -e;
+            }
+        }
+
+        impl<'a, $($lt,)? T: Scalar> AddAssign<&'a Vector<T>> for $target {
+            /// `y += x`, dispatched to `axpy`. Panics if the lengths differ.
+            fn add_assign(&mut self, x: &'a Vector<T>) {
+                *self += Scaled::new(T::one(), x.as_view());
+            }
+        }
+
+        impl<'a, $($lt,)? T: Scalar> SubAssign<&'a Vector<T>> for $target {
+            /// `y -= x`, dispatched to `axpy`. Panics if the lengths differ.
+            fn sub_assign(&mut self, x: &'a Vector<T>) {
+                *self += Scaled::new(-T::one(), x.as_view());
+            }
+        }
+
+        impl<'a, $($lt,)? T: Scalar> AddAssign<LinComb<'a, T>> for $target {
+            /// `z += a * x + b * y` in a single pass. Panics if the lengths differ.
+            fn add_assign(&mut self, e: LinComb<'a, T>) {
+                lincomb(&mut $view(self), e, true);
+            }
+        }
+
+        impl<$($lt,)? T: Scalar> MulAssign<T> for $target {
+            /// `x *= a`, dispatched to `scal`.
+            fn mul_assign(&mut self, alpha: T) {
+                scal_view(alpha, &mut $view(self));
+            }
+        }
+    )
+}
+
+fn vector_view<T: Scalar>(v: &mut Vector<T>) -> VecMut<'_, T> {
+    v.as_view_mut()
+}
+
+fn reborrow<'s, T>(v: &'s mut VecMut<'_, T>) -> VecMut<'s, T> {
+    v.rb_mut()
+}
+
+impl_assign_ops!(Vector<T>, vector_view,);
+impl_assign_ops!(VecMut<'v, T>, reborrow, 'v);
+
+impl<T: Scalar> Vector<T> {
+    /// Unconjugated dot product, dispatched to `dot`. Panics if the lengths differ.
+    pub fn dot<'b>(&self, other: impl Into<VecRef<'b, T>>) -> T
+    where T: 'b,
+    {
+        self.as_view().dot(other)
+    }
+
+    /// Overwrites `self` with `a * x + b * y` in a single pass. Panics if the lengths differ.
+    pub fn assign(&mut self, e: LinComb<'_, T>) {
+        lincomb(&mut self.as_view_mut(), e, false);
+    }
+}
+
+impl<'a, T: Scalar> VecRef<'a, T> {
+    /// Unconjugated dot product, dispatched to `dot`. Panics if the lengths differ.
+    pub fn dot<'b>(&self, other: impl Into<VecRef<'b, T>>) -> T
+    where T: 'b,
+    {
+        let other = other.into();
+        expect_len(self.len(), other.len());
+        dot_view(*self, other).unwrap()
+    }
+}
+
+impl<'a, T: Scalar> VecMut<'a, T> {
+    /// Overwrites `self` with `a * x + b * y` in a single pass. Panics if the lengths differ.
+    pub fn assign(&mut self, e: LinComb<'_, T>) {
+        lincomb(self, e, false);
+    }
+}
+
+impl<'a, T: Scalar> From<Scaled<'a, T>> for Vector<T> {
+    /// Evaluates `a * x` into a new vector.
+    fn from(e: Scaled<'a, T>) -> Self {
+        let mut v = Vector::zeros(e.x.len());
+        v += e;
+        v
+    }
+}
+
+impl<'a, T: Scalar> From<LinComb<'a, T>> for Vector<T> {
+    /// Evaluates `a * x + b * y` into a new vector in a single pass.
+    fn from(e: LinComb<'a, T>) -> Self {
+        let mut v = Vector::zeros(e.a.x.len());
+        v.assign(e);
+        v
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expressions_read_like_math() {
+        let x = Vector::from_slice(&[1.0_f64, 2.0, 3.0]);
+        let mut y = Vector::from_slice(&[1.0_f64, 1.0, 1.0]);
+
+        y += 2.0 * &x;
+        assert_eq!(*y, [3.0, 5.0, 7.0]);
+        y -= &x;
+        y *= 0.5;
+        assert_eq!(*y, [1.0, 1.5, 2.0]);
+        assert_eq!(x.dot(&y), 10.0);
+
+        let z = Vector::from(2.0 * &x - 1.0 * &y);
+        assert_eq!(*z, [1.0, 2.5, 4.0]);
+
+        let mut buf = [0.0_f64; 6];
+        let mut w = VecMut::new(&mut buf, 5, 3, -2).unwrap();
+        w.assign(&x * 1.0 + &y * 1.0);
+        w += &x;
+        assert_eq!(buf, [0.0, 8.0, 0.0, 5.5, 0.0, 3.0]);
+    }
+}
@@ -0,0 +1,309 @@
+use crate::dispatch::Kernels;
+use crate::scalar::{Scalar, RealScalar};
+use crate::simd::{Simd, Portable};
+use crate::utils::get_first_index;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::simd::{Avx2, Sse2};
+
+// Level 1 kernels on contiguous vectors, written once against `Simd`. Each is `#[inline(always)]` and only
+// reaches the backend's instructions through an entry point generated by `kernels!` below.
+
+#[inline(always)]
+pub(crate) unsafe fn asum<S: Simd>(n: usize, x: *const S::Elem) -> S::Elem {
+    let (mut acc0, mut acc1) = (S::zero(), S::zero());
+    let mut i = 0;
+    while i + 2 * S::LANES <= n {
+        acc0 = S::add(acc0, S::abs(S::load(x.add(i))));
+        acc1 = S::add(acc1, S::abs(S::load(x.add(i + S::LANES))));
+        i += 2 * S::LANES;
+    }
+    let mut result = S::hsum(S::add(acc0, acc1));
+    for i in i .. n {
+        result += (*x.add(i)).abs();
+    }
+    result
+}
+
+#[inline(always)]
+pub(crate) unsafe fn axpy<S: Simd>(n: usize, a: S::Elem, x: *const S::Elem, y: *mut S::Elem) {
+    let va = S::splat(a);
+    let mut i = 0;
+    while i + S::LANES <= n {
+        S::store(y.add(i), S::fma(va, S::load(x.add(i)), S::load(y.add(i))));
+        i += S::LANES;
+    }
+    for i in i .. n {
+        *y.add(i) += a * *x.add(i);
+    }
+}
+
+#[inline(always)]
+pub(crate) unsafe fn dot<S: Simd>(n: usize, x: *const S::Elem, y: *const S::Elem) -> S::Elem {
+    let (mut acc0, mut acc1) = (S::zero(), S::zero());
+    let mut i = 0;
+    while i + 2 * S::LANES <= n {
+        acc0 = S::fma(S::load(x.add(i)), S::load(y.add(i)), acc0);
+        acc1 = S::fma(S::load(x.add(i + S::LANES)), S::load(y.add(i + S::LANES)), acc1);
+        i += 2 * S::LANES;
+    }
+    let mut result = S::hsum(S::add(acc0, acc1));
+    for i in i .. n {
+        result += *x.add(i) * *y.add(i);
+    }
+    result
+}
+
+#[inline(always)]
+pub(crate) unsafe fn rot<S: Simd>(n: usize, x: *mut S::Elem, y: *mut S::Elem, c: S::Elem, s: S::Elem) {
+    let (vc, vs) = (S::splat(c), S::splat(s));
+    let mut i = 0;
+    while i + S::LANES <= n {
+        let (vx, vy) = (S::load(x.add(i)), S::load(y.add(i)));
+        S::store(x.add(i), S::fma(vc, vx, S::mul(vs, vy)));
+        S::store(y.add(i), S::fms(vc, vy, S::mul(vs, vx)));
+        i += S::LANES;
+    }
+    for i in i .. n {
+        let (px, py) = (x.add(i), y.add(i));
+        let temp = c * *px + s * *py;
+        *py = c * *py - s * *px;
+        *px = temp;
+    }
+}
+
+#[inline(always)]
+pub(crate) unsafe fn scal<S: Simd>(n: usize, a: S::Elem, x: *mut S::Elem) {
+    let va = S::splat(a);
+    let mut i = 0;
+    while i + S::LANES <= n {
+        S::store(x.add(i), S::mul(va, S::load(x.add(i))));
+        i += S::LANES;
+    }
+    for i in i .. n {
+        *x.add(i) *= a;
+    }
+}
+
+// Strided variants, taking each vector BLAS-style from the start of its storage with a nonzero increment, a
+// negative one walking the storage from its end. Strided operands go through `Simd::gather` and `Simd::scatter`.
+
+#[inline(always)]
+pub(crate) unsafe fn asum_strided<S: Simd>(n: usize, x: *const S::Elem, incx: isize) -> S::Elem {
+    if n == 0 {
+        return S::Elem::zero();
+    }
+    let x = x.add(get_first_index(n, incx));
+    let mut acc = S::zero();
+    let mut i = 0;
+    while i + S::LANES <= n {
+        acc = S::add(acc, S::abs(S::gather(x.offset(i as isize * incx), incx)));
+        i += S::LANES;
+    }
+    let mut result = S::hsum(acc);
+    for i in i .. n {
+        result += (*x.offset(i as isize * incx)).abs();
+    }
+    result
+}
+
+#[inline(always)]
+pub(crate) unsafe fn axpy_strided<S: Simd>(n: usize, a: S::Elem, x: *const S::Elem, incx: isize, y: *mut S::Elem,
+                                           incy: isize) {
+    if n == 0 {
+        return;
+    }
+    let (x, y) = (x.add(get_first_index(n, incx)), y.add(get_first_index(n, incy)));
+    let va = S::splat(a);
+    let mut i = 0;
+    while i + S::LANES <= n {
+        let (px, py) = (x.offset(i as isize * incx), y.offset(i as isize * incy));
+        S::scatter(py, incy, S::fma(va, S::gather(px, incx), S::gather(py, incy)));
+        i += S::LANES;
+    }
+    for i in i .. n {
+        *y.offset(i as isize * incy) += a * *x.offset(i as isize * incx);
+    }
+}
+
+#[inline(always)]
+pub(crate) unsafe fn dot_strided<S: Simd>(n: usize, x: *const S::Elem, incx: isize, y: *const S::Elem,
+                                          incy: isize) -> S::Elem {
+    if n == 0 {
+        return S::Elem::zero();
+    }
+    let (x, y) = (x.add(get_first_index(n, incx)), y.add(get_first_index(n, incy)));
+    let mut acc = S::zero();
+    let mut i = 0;
+    while i + S::LANES <= n {
+        acc = S::fma(S::gather(x.offset(i as isize * incx), incx), S::gather(y.offset(i as isize * incy), incy), acc);
+        i += S::LANES;
+    }
+    let mut result = S::hsum(acc);
+    for i in i .. n {
+        result += *x.offset(i as isize * incx) * *y.offset(i as isize * incy);
+    }
+    result
+}
+
+#[inline(always)]
+pub(crate) unsafe fn scal_strided<S: Simd>(n: usize, a: S::Elem, x: *mut S::Elem, incx: isize) {
+    if n == 0 {
+        return;
+    }
+    let x = x.add(get_first_index(n, incx));
+    let va = S::splat(a);
+    let mut i = 0;
+    while i + S::LANES <= n {
+        let px = x.offset(i as isize * incx);
+        S::scatter(px, incx, S::mul(va, S::gather(px, incx)));
+        i += S::LANES;
+    }
+    for i in i .. n {
+        *x.offset(i as isize * incx) *= a;
+    }
+}
+
+/// table of the kernels on backend `$s`, each instantiated in an entry point enabling `$features`
+macro_rules! kernels {
+    ($name:ident, $s:ty, $t:ident, $($features:literal)?, reductions: $reductions:literal,
+     strided: $strided:literal) => {
+        pub(crate) const $name: Kernels<$t> = {
+            $(#[target_feature(enable = $features)])?
+            unsafe fn asum_entry(n: usize, x: *const $t) -> $t { asum::<$s>(n, x) }
+            $(#[target_feature(enable = $features)])?
+            unsafe fn axpy_entry(n: usize, a: $t, x: *const $t, y: *mut $t) { axpy::<$s>(n, a, x, y) }
+            $(#[target_feature(enable = $features)])?
+            unsafe fn dot_entry(n: usize, x: *const $t, y: *const $t) -> $t { dot::<$s>(n, x, y) }
+            $(#[target_feature(enable = $features)])?
+            unsafe fn rot_entry(n: usize, x: *mut $t, y: *mut $t, c: $t, s: $t) { rot::<$s>(n, x, y, c, s) }
+            $(#[target_feature(enable = $features)])?
+            unsafe fn scal_entry(n: usize, a: $t, x: *mut $t) { scal::<$s>(n, a, x) }
+            $(#[target_feature(enable = $features)])?
+            unsafe fn asum_strided_entry(n: usize, x: *const $t, incx: isize) -> $t {
+                asum_strided::<$s>(n, x, incx)
+            }
+            $(#[target_feature(enable = $features)])?
+            unsafe fn axpy_strided_entry(n: usize, a: $t, x: *const $t, incx: isize, y: *mut $t, incy: isize) {
+                axpy_strided::<$s>(n, a, x, incx, y, incy)
+            }
+            $(#[target_feature(enable = $features)])?
+            unsafe fn dot_strided_entry(n: usize, x: *const $t, incx: isize, y: *const $t, incy: isize) -> $t {
+                dot_strided::<$s>(n, x, incx, y, incy)
+            }
+            $(#[target_feature(enable = $features)])?
+            unsafe fn scal_strided_entry(n: usize, a: $t, x: *mut $t, incx: isize) {
+                scal_strided::<$s>(n, a, x, incx)
+            }
+            Kernels {
+                asum: if $reductions { Some(asum_entry) } else { None },
+                axpy: axpy_entry,
+                dot: if $reductions { Some(dot_entry) } else { None },
+                rot: rot_entry,
+                scal: scal_entry,
+                asum_strided: if $strided { Some(asum_strided_entry) } else { None },
+                axpy_strided: if $strided { Some(axpy_strided_entry) } else { None },
+                dot_strided: if $strided { Some(dot_strided_entry) } else { None },
+                scal_strided: if $strided { Some(scal_strided_entry) } else { None },
+                reproducible: false,
+            }
+        };
+    };
+}
+
+// The one-lane reductions would only change the summation order of the generic loops, and SSE2 has no gather,
+// loading strided lanes one by one gains nothing over the generic loops either.
+kernels!(PORTABLE_F32, Portable<f32>, f32, , reductions: false, strided: false);
+kernels!(PORTABLE_F64, Portable<f64>, f64, , reductions: false, strided: false);
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+kernels!(SSE2_F32, Sse2<f32>, f32, "sse2", reductions: true, strided: false);
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+kernels!(SSE2_F64, Sse2<f64>, f64, "sse2", reductions: true, strided: false);
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+kernels!(AVX2_F32, Avx2<f32>, f32, "avx2,fma", reductions: true, strided: true);
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+kernels!(AVX2_F64, Avx2<f64>, f64, "avx2,fma", reductions: true, strided: true);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// runs every kernel of `k` and the one-lane kernels on vectors of each length up to 40, to cover the tails
+    fn check_against_portable(k: Kernels<f64>) {
+        for n in 0 .. 40 {
+            let x: Vec<f64> = (0 .. n).map(|i| ((i * 37 % 101) as f64 - 50.0) / 7.0).collect();
+            let y: Vec<f64> = (0 .. n).map(|i| ((i * 53 % 97) as f64 - 48.0) / 3.0).collect();
+            let bound = 4.0 * n as f64 * f64::EPSILON * x.iter().zip(&y).map(|(a, b)| (a * b).abs()).sum::<f64>();
+            let close = |a: &[f64], b: &[f64]| {
+                a.iter().zip(b).all(|(a, b)| (a - b).abs() <= 2.0 * f64::EPSILON * a.abs().max(1.0))
+            };
+            // SAFETY: every vector holds `n` elements, the x86 tables are only run when the CPU supports them.
+            unsafe {
+                let expected = asum::<Portable<f64>>(n, x.as_ptr());
+                assert!((k.asum.unwrap()(n, x.as_ptr()) - expected).abs() <= 4.0 * n as f64 * f64::EPSILON * expected);
+                let expected = dot::<Portable<f64>>(n, x.as_ptr(), y.as_ptr());
+                assert!((k.dot.unwrap()(n, x.as_ptr(), y.as_ptr()) - expected).abs() <= bound);
+
+                let (mut y0, mut y1) = (y.clone(), y.clone());
+                axpy::<Portable<f64>>(n, 0.3, x.as_ptr(), y0.as_mut_ptr());
+                (k.axpy)(n, 0.3, x.as_ptr(), y1.as_mut_ptr());
+                assert!(close(&y0, &y1));
+
+                let (mut x0, mut y0, mut x1, mut y1) = (x.clone(), y.clone(), x.clone(), y.clone());
+                rot::<Portable<f64>>(n, x0.as_mut_ptr(), y0.as_mut_ptr(), 0.6, 0.8);
+                (k.rot)(n, x1.as_mut_ptr(), y1.as_mut_ptr(), 0.6, 0.8);
+                assert!(close(&x0, &x1) && close(&y0, &y1));
+
+                scal::<Portable<f64>>(n, -1.5, x0.as_mut_ptr());
+                (k.scal)(n, -1.5, x1.as_mut_ptr());
+                assert!(close(&x0, &x1));
+            }
+        }
+    }
+
+    #[test]
+    fn backends_match_the_portable_kernels() {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if is_x86_feature_detected!("sse2") {
+                check_against_portable(SSE2_F64);
+            }
+            if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+                check_against_portable(AVX2_F64);
+            }
+        }
+    }
+
+    #[test]
+    fn strided_kernels_match_the_portable_kernels() {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+            let k = AVX2_F32;
+            for &(incx, incy) in &[(3_isize, -2_isize), (-1, 4), (2, 1)] {
+                for n in 0 .. 30_usize {
+                    let x: Vec<f32> = (0 .. 3 * n).map(|i| ((i * 37 % 101) as f32 - 50.0) / 8.0).collect();
+                    let y: Vec<f32> = (0 .. 4 * n).map(|i| ((i * 53 % 97) as f32 - 48.0) / 4.0).collect();
+                    // SAFETY: `x` and `y` hold `n` elements at increments up to 3 and 4, and the CPU supports
+                    // the kernels.
+                    unsafe {
+                        let expected = asum_strided::<Portable<f32>>(n, x.as_ptr(), incx.abs());
+                        let actual = k.asum_strided.unwrap()(n, x.as_ptr(), incx.abs());
+                        assert!((actual - expected).abs() <= 4.0 * n as f32 * f32::EPSILON * expected);
+
+                        // the values are multiples of 1/32 small enough for every sum to be exact
+                        let expected = dot_strided::<Portable<f32>>(n, x.as_ptr(), incx, y.as_ptr(), incy);
+                        assert_eq!(k.dot_strided.unwrap()(n, x.as_ptr(), incx, y.as_ptr(), incy), expected);
+
+                        let (mut y0, mut y1) = (y.clone(), y.clone());
+                        axpy_strided::<Portable<f32>>(n, 0.5, x.as_ptr(), incx, y0.as_mut_ptr(), incy);
+                        k.axpy_strided.unwrap()(n, 0.5, x.as_ptr(), incx, y1.as_mut_ptr(), incy);
+                        assert_eq!(y0, y1);
+
+                        scal_strided::<Portable<f32>>(n, -1.5, y0.as_mut_ptr(), incy.abs());
+                        k.scal_strided.unwrap()(n, -1.5, y1.as_mut_ptr(), incy.abs());
+                        assert_eq!(y0, y1);
+                    }
+                }
+            }
+        }
+    }
+}
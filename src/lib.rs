@@ -1,142 +1,287 @@
 #![allow(unused)]
-
-use std::ops::{Add, AddAssign, Sub, SubAssign, Neg, Mul, Div};
+#![allow(clippy::too_many_arguments)]
 
 mod utils;
+mod strided;
+mod dispatch;
+mod parallel;
 
-pub trait Numeric {
-    fn sqrt(self) -> Self;
-    fn sin(self)  -> Self;
-    fn acos(self) -> Self;
-    fn acosh(self) -> Self;
-}
-impl Numeric for f32 {
-    fn sqrt(self) -> Self { self.sqrt() }
-    fn sin(self)  -> Self { self.sin() }
-    fn acos(self) -> Self { self.acos() }
-    fn acosh(self) -> Self { self.acosh() }
-}
-impl Numeric for f64 {
-    fn sqrt(self) -> Self { self.sqrt() }
-    fn sin(self)  -> Self { self.sin() }
-    fn acos(self) -> Self { self.acos() }
-    fn acosh(self) -> Self { self.acosh() }
-}
+mod error;
+pub use error::BlasError;
 
-#[derive(Debug, Default, Copy, Clone)]
-pub struct Complex<T> {
-    pub re: T,
-    pub im: T,
-}
+mod xerbla;
+pub use xerbla::XerblaHandler;
+pub use xerbla::{set_xerbla, set_thread_xerbla, xerbla};
+pub use xerbla::{xerbla_silent, xerbla_log, xerbla_panic};
 
-//impl<T> Numeric for Complex<T> {
-//
-//}
+mod trace;
+pub use trace::{Arg, VerboseHandler, verbose, set_verbose, set_verbose_handler, verbose_stderr};
 
-impl<T: Copy + Mul<Output = T> + Add<Output = T> + Numeric> Complex<T> {
-    pub fn new(re: T, im: T) -> Self {
-        Self {
-            re: re,
-            im: im,
-        }
-    }
-    pub fn abs(self) -> T {
-        <T as Numeric>::sqrt(self.re * self.re + self.im * self.im)
-    }
-}
-impl<T: Add<Output = T>> Add for Complex<T> {
-    type Output = Self;
-    fn add(self, other: Self) -> Self {
-        Self{ re: self.re + other.re, im: self.im + other.im}
-    }
-}
-impl<T: AddAssign> AddAssign for Complex<T> {
-    fn add_assign(&mut self, other: Self) {
-        (*self).re += other.re;
-        (*self).im += other.im;
-    }
-}
-impl<T: Sub<Output = T>> Sub for Complex<T> {
-    type Output = Self;
-    fn sub(self, other: Self) -> Self {
-        Self{ re: self.re - other.re, im: self.im - other.im }
-    }
-}
-impl<T: SubAssign> SubAssign for Complex<T> {
-    fn sub_assign(&mut self, other: Self) {
-        (*self).re -= other.re;
-        (*self).im -= other.im;
-    }
-}
-impl<T: Neg<Output = T>> Neg for Complex<T> {
-    type Output = Self;
-    fn neg(self) -> Self {
-        Self { re: -self.re, im: -self.im}
-    }
-}
-impl<T: Copy + Mul<Output = T> + Sub<Output = T> + Add<Output = T>> Mul for Complex<T> {
-    type Output = Self;
-    fn mul(self, other: Self) -> Self {
-        Self{
-            re: self.re * other.re - self.im * other.im,
-            im: self.im * other.re + self.re * other.im,
-        }
-    }
-}
-impl<T: Copy + Div<Output = T> + Add<Output = T> + Mul<Output = T> + Sub<Output = T> + Numeric> Div for Complex<T> {
-    type Output = Self;
-    fn div(self, other: Self) -> Self {
-        let abs_other = Self::abs(other);
-        Self::new(
-            (self.re * other.re + self.im * other.im) / abs_other,
-            (self.im * other.re - self.re * other.im) / abs_other,
-        )
-    }
-}
+mod record;
+pub use record::{start_recording, record_to_file, stop_recording};
+pub use record::{read_trace, replay, replay_calls, replay_file, RecordedCall, Value, Mismatch, ReplayReport};
+
+#[cfg(feature = "sanitize")]
+mod sanitize;
+#[cfg(feature = "sanitize")]
+pub use sanitize::{Finding, FindingKind, SanitizeHandler, sanitize_log, sanitize_panic, set_sanitize_handler,
+                   set_thread_sanitize_handler};
+
+mod stats;
+pub use stats::{Stats, StatsSnapshot, RoutineStats, set_stats, stats_enabled, global_stats};
+
+mod context;
+pub use context::{Context, Isa};
+
+mod workspace;
+pub use workspace::{ScratchPool, ScratchAllocator, HeapAllocator, Arena};
+
+mod scalar;
+pub use scalar::{Scalar, RealScalar, ComplexScalar};
+
+mod complex;
+pub use complex::{Complex, Complex32, Complex64};
 
-pub type Complex32 = Complex<f32>;
-pub type Complex64 = Complex<f64>;
+mod split;
+pub use split::{SplitComplex, SplitComplexMut};
+pub use split::{axpy_split, caxpy_split, zaxpy_split};
+pub use split::{dotu_split, cdotu_split, zdotu_split, dotc_split, cdotc_split, zdotc_split};
+pub use split::{scal_split, cscal_split, zscal_split};
+pub use split::{asum_split, scasum_split, dzasum_split};
+pub use split::{nrm2_split, scnrm2_split, dznrm2_split};
 
-#[macro_use]
-mod macros;
+mod simd;
+mod kernels;
+
+mod view;
+pub use view::{VecRef, VecMut, Iter, IterMut};
+
+mod matview;
+pub use matview::{MatRef, MatMut};
+
+mod aligned;
+pub use aligned::ALIGNMENT;
+
+mod vector;
+pub use vector::Vector;
+
+mod matrix;
+pub use matrix::Matrix;
+
+mod expr;
+pub use expr::{Scaled, LinComb};
 
 mod asum;
+pub use asum::asum;
 pub use asum::sasum;
 pub use asum::dasum;
+pub use asum::{asum_view, sasum_view, dasum_view};
+pub use asum::{asum_unchecked, sasum_unchecked, dasum_unchecked};
 
 mod axpy;
+pub use axpy::axpy;
 pub use axpy::saxpy;
 pub use axpy::daxpy;
+pub use axpy::{axpy_view, saxpy_view, daxpy_view};
+pub use axpy::{axpy_unchecked, saxpy_unchecked, daxpy_unchecked};
 
 mod copy;
+pub use copy::copy;
 pub use copy::scopy;
 pub use copy::dcopy;
+pub use copy::{copy_view, scopy_view, dcopy_view};
+pub use copy::{copy_unchecked, scopy_unchecked, dcopy_unchecked};
+pub use copy::{copy_within, scopy_within, dcopy_within};
+pub use copy::{copy_within_workspace, scopy_within_workspace, dcopy_within_workspace};
 
 mod dot;
+pub use dot::dot;
 pub use dot::sdot;
 pub use dot::ddot;
+pub use dot::{dot_view, sdot_view, ddot_view};
+pub use dot::{dot_unchecked, sdot_unchecked, ddot_unchecked};
 
 mod rot;
+pub use rot::rot;
 pub use rot::srot;
 pub use rot::drot;
+pub use rot::{rot_view, srot_view, drot_view};
+pub use rot::{rot_unchecked, srot_unchecked, drot_unchecked};
+pub use rot::{rot_within, srot_within, drot_within};
+
+mod nrm2;
+pub use nrm2::nrm2;
+pub use nrm2::snrm2;
+pub use nrm2::dnrm2;
+pub use nrm2::{nrm2_view, snrm2_view, dnrm2_view};
+pub use nrm2::{nrm2_unchecked, snrm2_unchecked, dnrm2_unchecked};
+
+mod batch;
+pub use batch::{axpy_batch, saxpy_batch, daxpy_batch};
+pub use batch::{copy_batch, scopy_batch, dcopy_batch};
+pub use batch::{dot_batch, sdot_batch, ddot_batch};
+pub use batch::{rot_batch, srot_batch, drot_batch};
+pub use batch::{scal_batch, sscal_batch, dscal_batch};
+pub use batch::{axpy_batch_strided, saxpy_batch_strided, daxpy_batch_strided};
+pub use batch::{dot_batch_strided, sdot_batch_strided, ddot_batch_strided};
+pub use batch::{scal_batch_strided, sscal_batch_strided, dscal_batch_strided};
+pub use batch::{nrm2_batch_strided, snrm2_batch_strided, dnrm2_batch_strided};
 
 mod rotg;
+pub use rotg::rotg;
 pub use rotg::srotg;
 pub use rotg::drotg;
 
 mod scal;
+pub use scal::scal;
 pub use scal::sscal;
 pub use scal::dscal;
+pub use scal::{scal_view, sscal_view, dscal_view};
+pub use scal::{scal_unchecked, sscal_unchecked, dscal_unchecked};
 
 mod swap;
+pub use swap::swap;
 pub use swap::sswap;
 pub use swap::dswap;
+pub use swap::{swap_view, sswap_view, dswap_view};
+pub use swap::{swap_unchecked, sswap_unchecked, dswap_unchecked};
+pub use swap::{swap_within, sswap_within, dswap_within};
 
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     #[test]
     fn it_works() {
         assert_eq!(2 + 2, 4);
     }
+
+    #[test]
+    fn errors_name_the_offending_argument() {
+        let x = [1.0_f32; 4];
+        let mut y = [0.0_f32; 4];
+
+        assert_eq!(saxpy(-1, 1.0, &x, 1, &mut y, 1), Err(BlasError::InvalidDimension { pos: 1, name: "n", value: -1 }));
+        assert_eq!(saxpy(2, 1.0, &x, 0, &mut y, 1), Err(BlasError::InvalidIncrement { pos: 4, name: "incx", value: 0 }));
+        assert_eq!(saxpy(3, 1.0, &x, 1, &mut y, 2), Err(BlasError::SliceTooShort { pos: 5, name: "y", required: 5, actual: 4 }));
+        assert_eq!(sscal(2, 2.0, &mut y, -1), Err(BlasError::InvalidIncrement { pos: 4, name: "incx", value: -1 }));
+        assert_eq!(saxpy(0, 1.0, &x, 1, &mut y, 1), Ok(()));
+
+        assert_eq!(saxpy(4, 2.0, &x, 1, &mut y, -1), Ok(()));
+        assert_eq!(y, [2.0; 4]);
+    }
+
+    #[test]
+    fn most_negative_increment_is_handled() {
+        let x = [2.0_f64, 3.0];
+        let mut y = [1.0_f64, 1.0];
+        assert_eq!(daxpy(1, 1.0, &x, isize::MIN, &mut y, 1), Ok(()));
+        assert_eq!(y, [3.0, 1.0]);
+        assert_eq!(ddot(1, &x, isize::MIN, &x, 1), Ok(4.0));
+        assert_eq!(dasum_view(VecRef::from_blas(1, &x, isize::MIN).unwrap()), 2.0);
+        assert_eq!(daxpy(2, 1.0, &x, isize::MIN, &mut y, 1),
+                   Err(BlasError::SliceTooShort { pos: 3, name: "x", required: isize::MIN.unsigned_abs() + 1, actual: 2 }));
+    }
+
+    #[test]
+    fn xerbla_reports_routine_and_argument() {
+        let x = [1.0_f64; 2];
+        let previous = set_thread_xerbla(Some(xerbla_panic));
+        let result = std::panic::catch_unwind(|| ddot(3, &x, 1, &x, 1));
+        set_thread_xerbla(previous);
+
+        let err = result.unwrap_err();
+        let message = err.downcast_ref::<String>().unwrap();
+        assert!(message.starts_with("On entry to DDOT parameter 2 (x)"), "{}", message);
+        assert_eq!(ddot(3, &x, 1, &x, 1), Err(BlasError::SliceTooShort { pos: 2, name: "x", required: 3, actual: 2 }));
+    }
+
+    #[test]
+    fn generic_kernels_accept_complex_scalars() {
+        let x = [Complex64::new(1.0, 2.0), Complex64::new(3.0, -1.0)];
+        let mut y = [Complex64::new(0.5, 0.0); 2];
+
+        axpy(2, Complex64::new(0.0, 1.0), &x, 1, &mut y, 1).unwrap();
+        assert_eq!(y, [Complex64::new(-1.5, 1.0), Complex64::new(1.5, 3.0)]);
+
+        assert_eq!(dot(2, &x, 1, &x, 1), Ok(Complex64::new(5.0, -2.0)));
+        assert_eq!(asum(2, &x, 1), Ok(7.0));
+        assert_eq!(Complex64::new(-3.0, 4.0).sqrt(), Complex64::new(1.0, 2.0));
+    }
+
+    #[test]
+    fn view_routines_match_blas_triples() {
+        let x = [1.0_f64, 2.0, 3.0, 4.0];
+        let mut y = [0.0_f64; 4];
+        let mut y_view = y.to_vec();
+
+        daxpy(2, 2.0, &x, -2, &mut y, 3).unwrap();
+        let xv = VecRef::from_blas(2, &x, -2).unwrap();
+        daxpy_view(2.0, xv, &mut VecMut::new(&mut y_view, 0, 2, 3).unwrap()).unwrap();
+        assert_eq!(y, [6.0, 0.0, 0.0, 2.0]);
+        assert_eq!(y_view, y);
+
+        assert_eq!(ddot_view(xv, VecRef::from_slice(&x[.. 2])), Ok(5.0));
+        assert_eq!(daxpy_view(1.0, xv, &mut VecMut::from_slice(&mut y)), Err(BlasError::DimensionMismatch { pos: 3, name: "y", expected: 2, actual: 4 }));
+    }
+
+    #[test]
+    fn within_routines_check_overlap() {
+        // rows 0 and 2 of a 3x2 column-major matrix interleave but do not overlap
+        let mut a = [1.0_f32, 0.0, 3.0, 2.0, 0.0, 4.0];
+        assert_eq!(srot_within(2, &mut a, 0, 3, 2, 3, 0.0, 1.0), Ok(()));
+        assert_eq!(a, [3.0, 0.0, -1.0, 4.0, 0.0, -2.0]);
+        assert_eq!(sswap_within(2, &mut a, 0, 2, 2, 2), Err(BlasError::Overlap { pos: 5, name: "offy" }));
+        assert_eq!(sswap_within(2, &mut a, 0, 3, 0, 1), Err(BlasError::Overlap { pos: 5, name: "offy" }));
+
+        let mut b = [1.0_f64, 2.0, 3.0, 4.0, 5.0];
+        dcopy_within(4, &mut b, 0, 1, 1, 1).unwrap();
+        assert_eq!(b, [1.0, 1.0, 2.0, 3.0, 4.0]);
+        dcopy_within(2, &mut b, 4, -2, 1, 1).unwrap();
+        assert_eq!(b, [1.0, 4.0, 2.0, 3.0, 4.0]);
+        dcopy_within(3, &mut b, 0, 2, 4, -1).unwrap();
+        assert_eq!(b, [1.0, 4.0, 4.0, 2.0, 1.0]);
+    }
+
+    #[test]
+    fn reserved_workspace_covers_copy_within() {
+        assert_eq!(dcopy_within_workspace(3, 0, 1, 1, 1), 0);
+        assert_eq!(dcopy_within_workspace(2, 0, 2, 3, -2), 0);
+        assert_eq!(dcopy_within_workspace(2, 0, 2, 0, -1), 0);
+        let lwork = dcopy_within_workspace(3, 0, 2, 4, -1);
+        assert_eq!(lwork, 24);
+        let arena = std::sync::Arc::new(Arena::new(lwork));
+        let ctx = Context::new().with_scratch(std::sync::Arc::new(ScratchPool::with_allocator(arena.clone())));
+        assert!(ctx.scratch().reserve(lwork));
+        let mut b = [1.0_f64, 2.0, 3.0, 4.0, 5.0];
+        ctx.install(|| dcopy_within(3, &mut b, 0, 2, 4, -1)).unwrap();
+        assert_eq!(b, [1.0, 2.0, 5.0, 3.0, 1.0]);
+        assert_eq!(arena.used(), 24);
+    }
+
+    #[test]
+    fn unchecked_routines_follow_cblas_pointer_conventions() {
+        let x = [1.0_f64, 2.0, 3.0, 4.0];
+        let mut y = [10.0_f64, 20.0];
+        // negative increments start from the end of the array, as in CBLAS
+        unsafe { daxpy_unchecked(2, 1.0, x.as_ptr(), -2, y.as_mut_ptr(), 1) };
+        assert_eq!(y, [13.0, 21.0]);
+        assert_eq!(unsafe { ddot_unchecked(2, x.as_ptr(), 2, y.as_ptr(), -1) }, 21.0 + 39.0);
+        assert_eq!(unsafe { dasum_unchecked(2, x.as_ptr().add(1), 2) }, 6.0);
+
+        let mut a = [1.0_f32, 2.0, 3.0];
+        let mut b = [4.0_f32, 5.0, 6.0];
+        unsafe { sswap_unchecked(3, a.as_mut_ptr(), 1, b.as_mut_ptr(), -1) };
+        assert_eq!((a, b), ([6.0, 5.0, 4.0], [3.0, 2.0, 1.0]));
+        unsafe { srot_unchecked(3, a.as_mut_ptr(), 1, b.as_mut_ptr(), 1, 0.0, 1.0) };
+        assert_eq!((a, b), ([3.0, 2.0, 1.0], [-6.0, -5.0, -4.0]));
+    }
+
+    #[test]
+    fn nrm2_scales_to_avoid_overflow() {
+        assert_eq!(dnrm2(2, &[3e300, 4e300], 1), Ok(5e300));
+        assert_eq!(snrm2(2, &[3.0, 9.0, -4.0], 2), Ok(5.0));
+        assert_eq!(snrm2(2, &[1.0, 1.0], -1), Err(BlasError::InvalidIncrement { pos: 3, name: "incx", value: -1 }));
+    }
 }
@@ -0,0 +1,170 @@
+use std::mem;
+use std::ops::{Index, IndexMut};
+use crate::aligned::{AlignedBuf, ALIGNMENT};
+use crate::error::BlasError;
+use crate::matview::{MatLayout, MatRef, MatMut};
+use crate::scalar::Scalar;
+
+/// Owned column-major matrix whose columns each start on an `ALIGNMENT`-byte boundary.
+///
+/// The leading dimension `ld` is `nrows` rounded up to a whole number of `ALIGNMENT` bytes; the padding rows
+/// are kept at zero and are not part of the matrix.
+#[derive(Debug)]
+pub struct Matrix<T> {
+    data: AlignedBuf<T>,
+    nrows: usize,
+    ncols: usize,
+    ld: usize,
+}
+
+/// smallest leading dimension `>= nrows` that keeps every column aligned
+fn padded_ld<T>(nrows: usize) -> usize {
+    let size = mem::size_of::<T>();
+    if size == 0 || ALIGNMENT % size != 0 {
+        return nrows.max(1);
+    }
+    let lanes = ALIGNMENT / size;
+    nrows.max(1).div_ceil(lanes) * lanes
+}
+
+impl<T: Scalar> Matrix<T> {
+    pub fn zeros(nrows: usize, ncols: usize) -> Self {
+        let ld = padded_ld::<T>(nrows);
+        let len = ld.checked_mul(ncols).expect("capacity overflow");
+        Matrix { data: AlignedBuf::from_elem(T::zero(), len), nrows, ncols, ld }
+    }
+
+    /// `n x n` identity matrix.
+    pub fn identity(n: usize) -> Self {
+        Matrix::from_fn(n, n, |i, j| if i == j { T::one() } else { T::zero() })
+    }
+
+    /// Matrix whose element `(i, j)` is `f(i, j)`, evaluated column by column.
+    pub fn from_fn<F: FnMut(usize, usize) -> T>(nrows: usize, ncols: usize, mut f: F) -> Self {
+        let mut m = Matrix::zeros(nrows, ncols);
+        for j in 0 .. ncols {
+            for i in 0 .. nrows {
+                m.data[i + j * m.ld] = f(i, j);
+            }
+        }
+        m
+    }
+
+    /// Copies `nrows * ncols` elements stored column by column.
+    pub fn from_col_major(data: &[T], nrows: usize, ncols: usize) -> Result<Self, BlasError> {
+        check_data_len(data, nrows, ncols)?;
+        Ok(Matrix::from_fn(nrows, ncols, |i, j| data[i + j * nrows]))
+    }
+
+    /// Copies `nrows * ncols` elements stored row by row.
+    pub fn from_row_major(data: &[T], nrows: usize, ncols: usize) -> Result<Self, BlasError> {
+        check_data_len(data, nrows, ncols)?;
+        Ok(Matrix::from_fn(nrows, ncols, |i, j| data[i * ncols + j]))
+    }
+
+    pub fn nrows(&self) -> usize {
+        self.nrows
+    }
+
+    pub fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    /// Distance between the starts of two consecutive columns.
+    pub fn ld(&self) -> usize {
+        self.ld
+    }
+
+    /// Whole storage including the padding rows, column `j` starts at `j * ld()`.
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    /// Whole storage including the padding rows, column `j` starts at `j * ld()`.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+
+    fn layout(&self) -> MatLayout {
+        MatLayout { offset: 0, nrows: self.nrows, ncols: self.ncols, row_stride: 1, col_stride: self.ld as isize }
+    }
+
+    pub fn as_view(&self) -> MatRef<'_, T> {
+        MatRef::from_parts(&self.data, self.layout())
+    }
+
+    pub fn as_view_mut(&mut self) -> MatMut<'_, T> {
+        let layout = self.layout();
+        MatMut::from_parts(&mut self.data, layout)
+    }
+}
+
+fn check_data_len<T>(data: &[T], nrows: usize, ncols: usize) -> Result<(), BlasError> {
+    let expected = nrows.saturating_mul(ncols);
+    if data.len() != expected {
+        return Err(BlasError::DimensionMismatch { pos: 1, name: "data", expected, actual: data.len() });
+    }
+    Ok(())
+}
+
+impl<T: Copy> Clone for Matrix<T> {
+    fn clone(&self) -> Self {
+        Matrix { data: self.data.clone(), ..*self }
+    }
+}
+
+impl<T: Scalar> PartialEq for Matrix<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.nrows == other.nrows && self.ncols == other.ncols
+            && (0 .. self.ncols).all(|j| {
+                self.data[j * self.ld .. j * self.ld + self.nrows] == other.data[j * other.ld .. j * other.ld + other.nrows]
+            })
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        assert!(i < self.nrows && j < self.ncols, "index ({}, {}) out of range for {}x{} matrix", i, j, self.nrows, self.ncols);
+        &self.data[i + j * self.ld]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        assert!(i < self.nrows && j < self.ncols, "index ({}, {}) out of range for {}x{} matrix", i, j, self.nrows, self.ncols);
+        &mut self.data[i + j * self.ld]
+    }
+}
+
+impl<'a, T: Scalar> From<&'a Matrix<T>> for MatRef<'a, T> {
+    fn from(m: &'a Matrix<T>) -> Self {
+        m.as_view()
+    }
+}
+
+impl<'a, T: Scalar> From<&'a mut Matrix<T>> for MatMut<'a, T> {
+    fn from(m: &'a mut Matrix<T>) -> Self {
+        m.as_view_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ddot_view, Vector};
+
+    #[test]
+    fn storage_is_aligned_and_padded() {
+        let m = Matrix::<f64>::from_row_major(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 3, 2).unwrap();
+        assert_eq!(m.ld(), 8);
+        assert_eq!(m.as_slice().as_ptr() as usize % ALIGNMENT, 0);
+        assert_eq!(m[(2, 1)], 6.0);
+        assert_eq!(m, Matrix::from_col_major(&[1.0, 3.0, 5.0, 2.0, 4.0, 6.0], 3, 2).unwrap());
+
+        let v = Vector::from_fn(3, |i| i as f64);
+        assert_eq!(v.as_ptr() as usize % ALIGNMENT, 0);
+        assert_eq!(ddot_view(m.as_view().col(1), v.as_view()), Ok(16.0));
+        assert_eq!(Matrix::<f32>::identity(3).as_view().row(1).iter().copied().collect::<Vec<_>>(), [0.0, 1.0, 0.0]);
+    }
+}
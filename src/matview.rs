@@ -0,0 +1,403 @@
+use std::convert::TryFrom;
+use std::ops::{Index, IndexMut};
+use crate::error::BlasError;
+use crate::rot::rot_strided;
+use crate::scalar::Scalar;
+use crate::swap::swap_strided;
+use crate::view::{Strided, VecRef, VecMut};
+
+/// position of an `nrows x ncols` matrix whose element `(i, j)` is `offset + i * row_stride + j * col_stride`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct MatLayout {
+    pub offset: usize,
+    pub nrows: usize,
+    pub ncols: usize,
+    pub row_stride: isize,
+    pub col_stride: isize,
+}
+
+impl MatLayout {
+    /// checks that every element lies inside a slice of `data_len` elements and that distinct elements do not
+    /// share storage, arguments are numbered as in `MatRef::new(data, offset, nrows, ncols, row_stride, col_stride)`
+    pub fn new(data_len: usize, offset: usize, nrows: usize, ncols: usize, row_stride: isize, col_stride: isize) -> Result<Self, BlasError> {
+        if nrows == 0 || ncols == 0 {
+            // like `Strided::new`, an empty run gets a unit stride so its rows and columns are valid vectors
+            let row_stride = if row_stride == 0 { 1 } else { row_stride };
+            let col_stride = if col_stride == 0 { 1 } else { col_stride };
+            return Ok(MatLayout { offset: 0, nrows, ncols, row_stride, col_stride });
+        }
+        if nrows > 1 && row_stride == 0 {
+            return Err(BlasError::InvalidIncrement { pos: 5, name: "row_stride", value: row_stride });
+        }
+        if ncols > 1 && col_stride == 0 {
+            return Err(BlasError::InvalidIncrement { pos: 6, name: "col_stride", value: col_stride });
+        }
+
+        // the stride with the larger magnitude must step over a whole run of the other one
+        if nrows > 1 && ncols > 1 {
+            let (rs, cs) = (row_stride.unsigned_abs() as u128, col_stride.unsigned_abs() as u128);
+            let overlap = if rs >= cs { rs < (ncols as u128) * cs } else { cs < (nrows as u128) * rs };
+            if overlap {
+                return Err(if rs >= cs {
+                    BlasError::InvalidIncrement { pos: 5, name: "row_stride", value: row_stride }
+                } else {
+                    BlasError::InvalidIncrement { pos: 6, name: "col_stride", value: col_stride }
+                });
+            }
+        }
+
+        let row_span = (nrows as i128 - 1) * row_stride as i128;
+        let col_span = (ncols as i128 - 1) * col_stride as i128;
+        let below = row_span.min(0) + col_span.min(0);
+        let above = row_span.max(0) + col_span.max(0);
+        if (offset as i128) + below < 0 {
+            return Err(BlasError::InvalidOffset { pos: 2, name: "offset", value: offset, required: (-below) as usize });
+        }
+        let highest = offset as i128 + above;
+        if highest >= data_len as i128 {
+            let required = usize::try_from(highest + 1).unwrap_or(usize::MAX);
+            return Err(BlasError::SliceTooShort { pos: 1, name: "data", required, actual: data_len });
+        }
+        let row_stride = if nrows == 1 && row_stride == 0 { 1 } else { row_stride };
+        let col_stride = if ncols == 1 && col_stride == 0 { 1 } else { col_stride };
+        Ok(MatLayout { offset, nrows, ncols, row_stride, col_stride })
+    }
+
+    pub fn index(&self, i: usize, j: usize) -> usize {
+        (self.offset as isize + (i as isize) * self.row_stride + (j as isize) * self.col_stride) as usize
+    }
+
+    pub fn row(&self, i: usize) -> Strided {
+        assert!(i < self.nrows, "row {} out of range for matrix with {} rows", i, self.nrows);
+        let offset = if self.ncols == 0 { 0 } else { self.index(i, 0) };
+        Strided { offset, len: self.ncols, stride: self.col_stride }
+    }
+
+    pub fn col(&self, j: usize) -> Strided {
+        assert!(j < self.ncols, "column {} out of range for matrix with {} columns", j, self.ncols);
+        let offset = if self.nrows == 0 { 0 } else { self.index(0, j) };
+        Strided { offset, len: self.nrows, stride: self.row_stride }
+    }
+
+    pub fn sub(&self, row: usize, col: usize, nrows: usize, ncols: usize) -> Option<Self> {
+        if row.checked_add(nrows)? > self.nrows || col.checked_add(ncols)? > self.ncols {
+            return None;
+        }
+        let offset = if nrows == 0 || ncols == 0 { 0 } else { self.index(row, col) };
+        Some(MatLayout { offset, nrows, ncols, ..*self })
+    }
+
+    pub fn t(&self) -> Self {
+        MatLayout { offset: self.offset, nrows: self.ncols, ncols: self.nrows, row_stride: self.col_stride, col_stride: self.row_stride }
+    }
+}
+
+fn check_ld(ld: usize, min: usize) -> Result<isize, BlasError> {
+    if ld < min.max(1) || ld > isize::MAX as usize {
+        return Err(BlasError::InvalidDimension { pos: 4, name: "ld", value: ld as isize });
+    }
+    Ok(ld as isize)
+}
+
+/// Immutable view of an `nrows x ncols` matrix whose element `(i, j)` is `data[offset + i * row_stride + j * col_stride]`.
+///
+/// Rows and columns are handed out as `VecRef`s, so they go straight into `sdot_view`, `saxpy_view`, ...
+#[derive(Debug, Clone, Copy)]
+pub struct MatRef<'a, T> {
+    data: &'a [T],
+    layout: MatLayout,
+}
+
+impl<'a, T> MatRef<'a, T> {
+    /// View with explicit strides, which may be negative. Distinct elements must not share storage.
+    pub fn new(data: &'a [T], offset: usize, nrows: usize, ncols: usize, row_stride: isize, col_stride: isize) -> Result<Self, BlasError> {
+        Ok(MatRef { data, layout: MatLayout::new(data.len(), offset, nrows, ncols, row_stride, col_stride)? })
+    }
+
+    /// Column-major view with leading dimension `nrows`.
+    pub fn from_col_major(data: &'a [T], nrows: usize, ncols: usize) -> Result<Self, BlasError> {
+        MatRef::from_col_major_ld(data, nrows, ncols, nrows.max(1))
+    }
+
+    /// Column-major view with leading dimension `ld >= nrows`, like the `A, LDA` arguments of reference BLAS.
+    pub fn from_col_major_ld(data: &'a [T], nrows: usize, ncols: usize, ld: usize) -> Result<Self, BlasError> {
+        let ld = check_ld(ld, nrows)?;
+        MatRef::new(data, 0, nrows, ncols, 1, ld)
+    }
+
+    /// Row-major view with leading dimension `ncols`.
+    pub fn from_row_major(data: &'a [T], nrows: usize, ncols: usize) -> Result<Self, BlasError> {
+        MatRef::from_row_major_ld(data, nrows, ncols, ncols.max(1))
+    }
+
+    /// Row-major view with leading dimension `ld >= ncols`.
+    pub fn from_row_major_ld(data: &'a [T], nrows: usize, ncols: usize, ld: usize) -> Result<Self, BlasError> {
+        let ld = check_ld(ld, ncols)?;
+        MatRef::new(data, 0, nrows, ncols, ld, 1)
+    }
+
+    pub(crate) fn from_parts(data: &'a [T], layout: MatLayout) -> Self {
+        MatRef { data, layout }
+    }
+
+    pub fn nrows(&self) -> usize {
+        self.layout.nrows
+    }
+
+    pub fn ncols(&self) -> usize {
+        self.layout.ncols
+    }
+
+    pub fn row_stride(&self) -> isize {
+        self.layout.row_stride
+    }
+
+    pub fn col_stride(&self) -> isize {
+        self.layout.col_stride
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> Option<&'a T> {
+        if i < self.layout.nrows && j < self.layout.ncols { Some(&self.data[self.layout.index(i, j)]) } else { None }
+    }
+
+    /// Row `i` as a vector view of `ncols` elements.
+    pub fn row(&self, i: usize) -> VecRef<'a, T> {
+        VecRef::from_parts(self.data, self.layout.row(i))
+    }
+
+    /// Column `j` as a vector view of `nrows` elements.
+    pub fn col(&self, j: usize) -> VecRef<'a, T> {
+        VecRef::from_parts(self.data, self.layout.col(j))
+    }
+
+    /// View of the `nrows x ncols` block whose top-left element is `(row, col)`, `None` if it does not fit.
+    pub fn submatrix(&self, row: usize, col: usize, nrows: usize, ncols: usize) -> Option<Self> {
+        Some(MatRef { data: self.data, layout: self.layout.sub(row, col, nrows, ncols)? })
+    }
+
+    /// Transposed view, no data is moved.
+    pub fn t(self) -> Self {
+        MatRef { data: self.data, layout: self.layout.t() }
+    }
+}
+
+impl<'a, T> Index<(usize, usize)> for MatRef<'a, T> {
+    type Output = T;
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        assert!(i < self.layout.nrows && j < self.layout.ncols, "index ({}, {}) out of range for {}x{} matrix", i, j, self.layout.nrows, self.layout.ncols);
+        &self.data[self.layout.index(i, j)]
+    }
+}
+
+/// Mutable view of an `nrows x ncols` matrix whose element `(i, j)` is `data[offset + i * row_stride + j * col_stride]`.
+#[derive(Debug)]
+pub struct MatMut<'a, T> {
+    data: &'a mut [T],
+    layout: MatLayout,
+}
+
+impl<'a, T> MatMut<'a, T> {
+    /// View with explicit strides, which may be negative. Distinct elements must not share storage.
+    pub fn new(data: &'a mut [T], offset: usize, nrows: usize, ncols: usize, row_stride: isize, col_stride: isize) -> Result<Self, BlasError> {
+        let layout = MatLayout::new(data.len(), offset, nrows, ncols, row_stride, col_stride)?;
+        Ok(MatMut { data, layout })
+    }
+
+    /// Column-major view with leading dimension `nrows`.
+    pub fn from_col_major(data: &'a mut [T], nrows: usize, ncols: usize) -> Result<Self, BlasError> {
+        MatMut::from_col_major_ld(data, nrows, ncols, nrows.max(1))
+    }
+
+    /// Column-major view with leading dimension `ld >= nrows`, like the `A, LDA` arguments of reference BLAS.
+    pub fn from_col_major_ld(data: &'a mut [T], nrows: usize, ncols: usize, ld: usize) -> Result<Self, BlasError> {
+        let ld = check_ld(ld, nrows)?;
+        MatMut::new(data, 0, nrows, ncols, 1, ld)
+    }
+
+    /// Row-major view with leading dimension `ncols`.
+    pub fn from_row_major(data: &'a mut [T], nrows: usize, ncols: usize) -> Result<Self, BlasError> {
+        MatMut::from_row_major_ld(data, nrows, ncols, ncols.max(1))
+    }
+
+    /// Row-major view with leading dimension `ld >= ncols`.
+    pub fn from_row_major_ld(data: &'a mut [T], nrows: usize, ncols: usize, ld: usize) -> Result<Self, BlasError> {
+        let ld = check_ld(ld, ncols)?;
+        MatMut::new(data, 0, nrows, ncols, ld, 1)
+    }
+
+    pub(crate) fn from_parts(data: &'a mut [T], layout: MatLayout) -> Self {
+        MatMut { data, layout }
+    }
+
+    pub fn nrows(&self) -> usize {
+        self.layout.nrows
+    }
+
+    pub fn ncols(&self) -> usize {
+        self.layout.ncols
+    }
+
+    pub fn row_stride(&self) -> isize {
+        self.layout.row_stride
+    }
+
+    pub fn col_stride(&self) -> isize {
+        self.layout.col_stride
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> Option<&T> {
+        if i < self.layout.nrows && j < self.layout.ncols { Some(&self.data[self.layout.index(i, j)]) } else { None }
+    }
+
+    pub fn get_mut(&mut self, i: usize, j: usize) -> Option<&mut T> {
+        if i < self.layout.nrows && j < self.layout.ncols { Some(&mut self.data[self.layout.index(i, j)]) } else { None }
+    }
+
+    /// Immutable view of the same matrix.
+    pub fn as_ref(&self) -> MatRef<'_, T> {
+        MatRef { data: self.data, layout: self.layout }
+    }
+
+    /// Reborrows the view, so it can be passed by value and used again afterwards.
+    pub fn rb_mut(&mut self) -> MatMut<'_, T> {
+        MatMut { data: self.data, layout: self.layout }
+    }
+
+    /// Row `i` as a vector view of `ncols` elements.
+    pub fn row(&self, i: usize) -> VecRef<'_, T> {
+        VecRef::from_parts(self.data, self.layout.row(i))
+    }
+
+    /// Column `j` as a vector view of `nrows` elements.
+    pub fn col(&self, j: usize) -> VecRef<'_, T> {
+        VecRef::from_parts(self.data, self.layout.col(j))
+    }
+
+    /// Row `i` as a mutable vector view of `ncols` elements.
+    pub fn row_mut(&mut self, i: usize) -> VecMut<'_, T> {
+        VecMut::from_parts(self.data, self.layout.row(i))
+    }
+
+    /// Column `j` as a mutable vector view of `nrows` elements.
+    pub fn col_mut(&mut self, j: usize) -> VecMut<'_, T> {
+        VecMut::from_parts(self.data, self.layout.col(j))
+    }
+
+    /// View of the `nrows x ncols` block whose top-left element is `(row, col)`, `None` if it does not fit.
+    pub fn submatrix(&self, row: usize, col: usize, nrows: usize, ncols: usize) -> Option<MatRef<'_, T>> {
+        Some(MatRef { data: self.data, layout: self.layout.sub(row, col, nrows, ncols)? })
+    }
+
+    /// Mutable view of the `nrows x ncols` block whose top-left element is `(row, col)`, `None` if it does not fit.
+    pub fn submatrix_mut(&mut self, row: usize, col: usize, nrows: usize, ncols: usize) -> Option<MatMut<'_, T>> {
+        Some(MatMut { layout: self.layout.sub(row, col, nrows, ncols)?, data: self.data })
+    }
+
+    /// Transposed view, no data is moved.
+    pub fn t(self) -> Self {
+        MatMut { layout: self.layout.t(), data: self.data }
+    }
+
+    /// Interchanges rows `i` and `k`.
+    pub fn swap_rows(&mut self, i: usize, k: usize) {
+        if i != k {
+            swap_strided(self.data, self.layout.row(i), self.layout.row(k));
+        }
+    }
+
+    /// Interchanges columns `j` and `l`.
+    pub fn swap_cols(&mut self, j: usize, l: usize) {
+        if j != l {
+            swap_strided(self.data, self.layout.col(j), self.layout.col(l));
+        }
+    }
+}
+
+impl<'a, T: Scalar> MatMut<'a, T> {
+    /// Applies the plane rotation `(c, s)` to rows `i` and `k`, as `rot` does to `x = row(i)`, `y = row(k)`.
+    /// Panics if `i == k`.
+    pub fn rot_rows(&mut self, i: usize, k: usize, c: T, s: T) {
+        assert!(i != k, "cannot rotate row {} with itself", i);
+        rot_strided(self.data, self.layout.row(i), self.layout.row(k), c, s);
+    }
+
+    /// Applies the plane rotation `(c, s)` to columns `j` and `l`, as `rot` does to `x = col(j)`, `y = col(l)`.
+    /// Panics if `j == l`.
+    pub fn rot_cols(&mut self, j: usize, l: usize, c: T, s: T) {
+        assert!(j != l, "cannot rotate column {} with itself", j);
+        rot_strided(self.data, self.layout.col(j), self.layout.col(l), c, s);
+    }
+}
+
+impl<'a, T> Index<(usize, usize)> for MatMut<'a, T> {
+    type Output = T;
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        assert!(i < self.layout.nrows && j < self.layout.ncols, "index ({}, {}) out of range for {}x{} matrix", i, j, self.layout.nrows, self.layout.ncols);
+        &self.data[self.layout.index(i, j)]
+    }
+}
+
+impl<'a, T> IndexMut<(usize, usize)> for MatMut<'a, T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        assert!(i < self.layout.nrows && j < self.layout.ncols, "index ({}, {}) out of range for {}x{} matrix", i, j, self.layout.nrows, self.layout.ncols);
+        &mut self.data[self.layout.index(i, j)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{sdot_view, saxpy_view, dasum_view, dnrm2_view, dscal_view, ddot_view};
+
+    #[test]
+    fn rows_and_columns_feed_level1_kernels() {
+        // 2x3 column-major with ld = 3
+        let mut data = [1.0_f32, 2.0, -1.0, 3.0, 4.0, -1.0, 5.0, 6.0, -1.0];
+        let a = MatRef::from_col_major_ld(&data, 2, 3, 3).unwrap();
+        assert_eq!(a[(1, 2)], 6.0);
+        assert_eq!(sdot_view(a.row(0), a.row(1)), Ok(1.0 * 2.0 + 3.0 * 4.0 + 5.0 * 6.0));
+        assert_eq!(a.t().col(1).iter().copied().collect::<Vec<_>>(), [2.0, 4.0, 6.0]);
+        assert_eq!(a.submatrix(0, 1, 2, 2).unwrap()[(1, 0)], 4.0);
+
+        let mut m = MatMut::from_col_major_ld(&mut data, 2, 3, 3).unwrap();
+        let col0: Vec<f32> = m.col(0).iter().copied().collect();
+        saxpy_view(1.0, VecRef::from_slice(&col0), &mut m.col_mut(2)).unwrap();
+        assert_eq!(data[6 .. 8], [6.0, 8.0]);
+    }
+
+    #[test]
+    fn construction_rejects_overlap_and_short_slices() {
+        let data = [0.0_f64; 6];
+        assert_eq!(MatRef::new(&data, 0, 2, 3, 1, 1).unwrap_err(), BlasError::InvalidIncrement { pos: 5, name: "row_stride", value: 1 });
+        assert_eq!(MatRef::from_col_major(&data, 3, 3).unwrap_err(), BlasError::SliceTooShort { pos: 1, name: "data", required: 9, actual: 6 });
+        assert_eq!(MatRef::from_row_major_ld(&data, 2, 3, 2).unwrap_err(), BlasError::InvalidDimension { pos: 4, name: "ld", value: 2 });
+        assert!(MatRef::new(&data, 5, 2, 3, -1, -2).is_ok());
+    }
+
+    #[test]
+    fn rows_of_one_buffer_rotate_and_swap() {
+        let mut data = [1.0_f64, 2.0, 3.0, 4.0];
+        let mut m = MatMut::from_col_major(&mut data, 2, 2).unwrap();
+        m.rot_rows(0, 1, 0.0, 1.0);
+        assert_eq!(data, [2.0, -1.0, 4.0, -3.0]);
+        MatMut::from_col_major(&mut data, 2, 2).unwrap().swap_cols(0, 1);
+        assert_eq!(data, [4.0, -3.0, 2.0, -1.0]);
+    }
+
+    #[test]
+    fn rows_and_columns_of_empty_matrices_are_empty_vectors() {
+        let mut data = [0.0_f64; 0];
+        let wide = MatRef::new(&data, 0, 1, 0, 0, 0).unwrap();
+        assert_eq!(dasum_view(wide.row(0)), 0.0);
+        assert_eq!(dnrm2_view(wide.row(0)), 0.0);
+        assert_eq!(ddot_view(wide.row(0), wide.row(0)), Ok(0.0));
+        let tall = MatRef::new(&data, 0, 0, 1, 0, 0).unwrap();
+        assert_eq!(dasum_view(tall.col(0)), 0.0);
+        assert_eq!(dnrm2_view(tall.t().row(0)), 0.0);
+
+        let mut m = MatMut::new(&mut data, 0, 1, 0, 0, 0).unwrap();
+        dscal_view(2.0, &mut m.row_mut(0));
+        let mut m = MatMut::new(&mut data, 0, 0, 1, 0, 0).unwrap();
+        dscal_view(2.0, &mut m.col_mut(0));
+    }
+}
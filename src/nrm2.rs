@@ -0,0 +1,109 @@
+use crate::error::BlasError;
+use crate::scalar::{Scalar, RealScalar};
+use crate::xerbla::report;
+use crate::trace::{self, Arg};
+use crate::view::VecRef;
+use crate::utils::{check_n, check_inc_positive};
+use crate::strided::walk1;
+
+/// computes the Euclidean norm `sqrt(sum(|x[i]|^2))` of a vector, for any `Scalar` type
+///
+/// The sum of squares is accumulated with a running scale, as in reference BLAS, so the result neither
+/// overflows nor underflows when the norm itself is representable.
+pub fn nrm2<T>(n: isize, x: &[T], incx: isize) -> Result<T::Real, BlasError>
+where T: Scalar,
+{
+    let n_usize = check_n(n, 1)?;
+    check_inc_positive(n_usize, x, incx, 2, "x", "incx")?;
+
+    // SAFETY: the slice was checked to hold `n` elements at increment `incx`.
+    Ok(unsafe { nrm2_unchecked(n_usize, x.as_ptr(), incx) })
+}
+
+/// `nrm2` on a raw pointer, validated by debug assertions only, like CBLAS `cblas_?nrm2`
+///
+/// # Safety
+///
+/// `incx` must be positive and `x` valid for reads of `(n - 1) * incx + 1` elements.
+pub unsafe fn nrm2_unchecked<T>(n: usize, x: *const T, incx: isize) -> T::Real
+where T: Scalar,
+{
+    debug_assert!(n == 0 || !x.is_null());
+    debug_assert!(incx > 0, "incx must be positive");
+    let mut scale = T::Real::zero();
+    let mut ssq = T::Real::one();
+    walk1(n, x as *mut T, incx, |px| {
+        add_scaled_square((*px).re(), &mut scale, &mut ssq);
+        add_scaled_square((*px).im(), &mut scale, &mut ssq);
+    });
+
+    scale * ssq.sqrt()
+}
+
+/// adds `v^2` to the sum of squares `scale^2 * ssq`, rescaling when `|v|` exceeds `scale`
+fn add_scaled_square<R: RealScalar>(v: R, scale: &mut R, ssq: &mut R) {
+    if v == R::zero() {
+        return;
+    }
+    let absv = v.abs();
+    if *scale < absv {
+        let ratio = *scale / absv;
+        *ssq = R::one() + *ssq * ratio * ratio;
+        *scale = absv;
+    } else {
+        let ratio = absv / *scale;
+        *ssq += ratio * ratio;
+    }
+}
+
+/// computes the Euclidean norm of an `f32` vector
+pub fn snrm2(n: isize, x: &[f32], incx: isize) -> Result<f32, BlasError> {
+    let call = trace::begin("snrm2", &[("n", Arg::Int(n)), ("x", Arg::F32s(x)), ("incx", Arg::Int(incx))]);
+    let result = nrm2::<f32>(n, x, incx).map_err(|e| report("snrm2", e));
+    trace::end(call, result.is_ok(), &[("result", Arg::F32(result.unwrap_or_default()))]);
+    result
+}
+
+/// computes the Euclidean norm of an `f64` vector
+pub fn dnrm2(n: isize, x: &[f64], incx: isize) -> Result<f64, BlasError> {
+    let call = trace::begin("dnrm2", &[("n", Arg::Int(n)), ("x", Arg::F64s(x)), ("incx", Arg::Int(incx))]);
+    let result = nrm2::<f64>(n, x, incx).map_err(|e| report("dnrm2", e));
+    trace::end(call, result.is_ok(), &[("result", Arg::F64(result.unwrap_or_default()))]);
+    result
+}
+
+/// `snrm2` on a raw pointer without bounds checks
+///
+/// # Safety
+///
+/// See `nrm2_unchecked`.
+pub unsafe fn snrm2_unchecked(n: usize, x: *const f32, incx: isize) -> f32 {
+    nrm2_unchecked::<f32>(n, x, incx)
+}
+
+/// `dnrm2` on a raw pointer without bounds checks
+///
+/// # Safety
+///
+/// See `nrm2_unchecked`.
+pub unsafe fn dnrm2_unchecked(n: usize, x: *const f64, incx: isize) -> f64 {
+    nrm2_unchecked::<f64>(n, x, incx)
+}
+
+/// computes the Euclidean norm of a vector view, for any `Scalar` type
+pub fn nrm2_view<T>(x: VecRef<'_, T>) -> T::Real
+where T: Scalar,
+{
+    let (n, x, incx) = x.forward().as_blas();
+    nrm2(n, x, incx).unwrap_or_else(|_| unreachable!("views are validated on construction"))
+}
+
+/// `snrm2` on a vector view
+pub fn snrm2_view(x: VecRef<'_, f32>) -> f32 {
+    nrm2_view::<f32>(x)
+}
+
+/// `dnrm2` on a vector view
+pub fn dnrm2_view(x: VecRef<'_, f64>) -> f64 {
+    nrm2_view::<f64>(x)
+}
@@ -0,0 +1,93 @@
+use std::panic;
+use std::thread;
+use crate::context::{self, Context};
+
+/// value moved to the worker threads of `split`, whose caller vouches for it
+#[derive(Clone, Copy)]
+struct AssertSend<V>(V);
+
+// SAFETY: see `split`.
+unsafe impl<V> Send for AssertSend<V> {}
+unsafe impl<V> Sync for AssertSend<V> {}
+
+/// Runs `f(lo, hi)` on consecutive chunks `lo .. hi` of `0 .. n` on scoped threads and returns the results in
+/// chunk order, or `None` when the call should stay on the calling thread: the context in effect allows one
+/// thread only, or `n` is below twice its parallel threshold.
+///
+/// The calling thread runs the first chunk, the workers run the others under the caller's context.
+///
+/// # Safety
+///
+/// `f` runs on other threads: whatever it captures and returns must be safe to send there, which holds for the
+/// `f32` and `f64` kernels and for copies of `Send + Sync` elements, and the chunks it works on must not race.
+pub(crate) unsafe fn split<R>(n: usize, f: impl Fn(usize, usize) -> R) -> Option<Vec<R>> {
+    let settings = context::current_settings();
+    let threads = (n / settings.parallel_threshold).min(settings.max_threads);
+    if threads < 2 {
+        return None;
+    }
+    let ctx = Context::current();
+    let (f, ctx) = (AssertSend(&f), &ctx);
+    let bounds = |t: usize| (t * n / threads, (t + 1) * n / threads);
+    let results = thread::scope(|scope| {
+        let workers: Vec<_> = (1 .. threads)
+            .map(|t| {
+                let (lo, hi) = bounds(t);
+                scope.spawn(move || AssertSend(ctx.install(|| (f.0)(lo, hi))))
+            })
+            .collect();
+        let (lo, hi) = bounds(0);
+        let mut results = vec![(f.0)(lo, hi)];
+        for worker in workers {
+            results.push(worker.join().unwrap_or_else(|e| panic::resume_unwind(e)).0);
+        }
+        results
+    });
+    Some(results)
+}
+
+/// pointer to the storage of elements `lo .. hi` of the `n`-element vector stored at `p` with increment `inc`,
+/// as a BLAS vector of `hi - lo` elements with the same increment
+///
+/// # Safety
+///
+/// `p` must be valid for `n` elements at increment `inc`, and `lo < hi <= n`.
+pub(crate) unsafe fn chunk<T>(p: *mut T, n: usize, inc: isize, lo: usize, hi: usize) -> *mut T {
+    if inc > 0 {
+        p.add(lo * inc as usize)
+    } else {
+        p.add((n - hi) * inc.unsigned_abs())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Context;
+
+    #[test]
+    fn large_calls_split_over_the_context_threads() {
+        let n = 1003;
+        let x: Vec<f64> = (0 .. 2 * n).map(|i| (i % 7) as f64 - 3.0).collect();
+        let mut y: Vec<f64> = (0 .. n).map(|i| (i % 5) as f64).collect();
+        let serial = Context::new().with_max_threads(1);
+        let parallel = Context::new().with_max_threads(4).with_parallel_threshold(100);
+
+        let dot = |ctx: &Context| ctx.install(|| crate::ddot(n as isize, &x, -2, &y, 1));
+        assert_eq!(dot(&serial), dot(&parallel));
+        let asum = |ctx: &Context| ctx.install(|| crate::dasum(n as isize, &x, 2));
+        assert_eq!(asum(&serial), asum(&parallel));
+
+        let mut expected = y.clone();
+        serial.install(|| crate::daxpy(n as isize, 0.5, &x, 2, &mut expected, -1)).unwrap();
+        parallel.install(|| crate::daxpy(n as isize, 0.5, &x, 2, &mut y, -1)).unwrap();
+        assert_eq!(y, expected);
+
+        serial.install(|| crate::dscal(n as isize, 3.0, &mut expected, 1)).unwrap();
+        parallel.install(|| crate::dscal(n as isize, 3.0, &mut y, 1)).unwrap();
+        assert_eq!(y, expected);
+
+        let mut copied = vec![0.0; 2 * n];
+        parallel.install(|| crate::dcopy(n as isize, &y, 1, &mut copied, -2)).unwrap();
+        assert!(copied.iter().rev().skip(1).step_by(2).zip(&y).all(|(a, b)| a == b));
+    }
+}
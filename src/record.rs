@@ -0,0 +1,554 @@
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::cell::Cell;
+use std::sync::Mutex;
+use crate::context::{self, Context, Isa};
+use crate::trace::Arg;
+
+// Trace file layout, all numbers little-endian: the 8-byte magic `RSBLTRC1`, then one record per call:
+//
+//     routine: str, snapshot: u8, isa: u8, reproducible: u8, args: u8 count + arg*, outputs: u8 count + arg*
+//     str: u8 length + UTF-8 bytes
+//     arg: name: str, tag: u8, payload
+//
+// with `isa` 0 to 3 for `Isa::Scalar`, `Sse2`, `Avx2` and `Fma`, and the payloads: tag 0 `i64`, tag 1 `f32`, tag 2 `f64`, tag 3 `u64` length + `f32` data, tag 4 `u64`
+// length + `f64` data. Without snapshots, vectors keep their length but no data, and no outputs are stored.
+
+const MAGIC: &[u8; 8] = b"RSBLTRC1";
+
+/// longest vector filled with synthetic data, so that a corrupt length cannot make `read_trace` allocate at will
+const MAX_SYNTHETIC_LEN: usize = 1 << 26;
+
+struct Recorder {
+    out: BufWriter<Box<dyn Write + Send>>,
+    snapshots: bool,
+    error: Option<io::Error>,
+}
+
+static RECORDING: AtomicBool = AtomicBool::new(false);
+static RECORDER: Mutex<Option<Recorder>> = Mutex::new(None);
+
+thread_local! {
+    /// whether the thread is inside `replay`, whose calls are not recorded
+    static REPLAYING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// marks the thread as replaying until dropped
+struct Replaying(bool);
+
+impl Replaying {
+    fn start() -> Self {
+        Replaying(REPLAYING.with(|r| r.replace(true)))
+    }
+}
+
+impl Drop for Replaying {
+    fn drop(&mut self) {
+        REPLAYING.with(|r| r.set(self.0));
+    }
+}
+
+fn recorder() -> std::sync::MutexGuard<'static, Option<Recorder>> {
+    RECORDER.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Starts recording every call of a typed BLAS routine (`saxpy`, `ddot`, ...) to `writer`, replacing any
+/// recording in progress.
+///
+/// With `snapshots`, the input vectors and the outputs of each call are stored too, so that `replay` can
+/// re-execute the calls on the same data and compare the results; without, only the arguments and vector
+/// lengths are kept.
+pub fn start_recording<W: Write + Send + 'static>(writer: W, snapshots: bool) -> io::Result<()> {
+    stop_recording()?;
+    let mut out = BufWriter::new(Box::new(writer) as Box<dyn Write + Send>);
+    out.write_all(MAGIC)?;
+    *recorder() = Some(Recorder { out, snapshots, error: None });
+    RECORDING.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+/// `start_recording` to a new file at `path`.
+pub fn record_to_file<P: AsRef<Path>>(path: P, snapshots: bool) -> io::Result<()> {
+    start_recording(File::create(path)?, snapshots)
+}
+
+/// Stops recording and flushes the trace, reporting the first write error met while recording.
+pub fn stop_recording() -> io::Result<()> {
+    RECORDING.store(false, Ordering::Relaxed);
+    match recorder().take() {
+        Some(mut rec) => match rec.error.take() {
+            Some(e) => Err(e),
+            None => rec.out.flush(),
+        },
+        None => Ok(()),
+    }
+}
+
+/// whether calls on this thread are recorded
+pub(crate) fn recording() -> bool {
+    RECORDING.load(Ordering::Relaxed) && !REPLAYING.with(|r| r.get())
+}
+
+/// encodes the start of a record, `None` if recording stopped in the meantime
+pub(crate) fn begin(routine: &str, args: &[(&'static str, Arg<'_>)]) -> Option<(Vec<u8>, bool)> {
+    let snapshots = recorder().as_ref()?.snapshots;
+    let settings = context::current_settings();
+    let mut buf = Vec::new();
+    put_str(&mut buf, routine);
+    buf.push(snapshots as u8);
+    buf.push(settings.isa as u8);
+    buf.push(settings.reproducible as u8);
+    put_args(&mut buf, args, snapshots);
+    Some((buf, snapshots))
+}
+
+/// appends the outputs to a record started by `begin` and writes it to the trace
+pub(crate) fn end(mut buf: Vec<u8>, snapshots: bool, outputs: &[(&'static str, Arg<'_>)]) {
+    put_args(&mut buf, if snapshots { outputs } else { &[] }, snapshots);
+    if let Some(rec) = recorder().as_mut() {
+        if rec.error.is_none() {
+            rec.error = rec.out.write_all(&buf).err();
+        }
+    }
+}
+
+fn put_str(buf: &mut Vec<u8>, s: &str) {
+    buf.push(s.len() as u8);
+    buf.extend_from_slice(s.as_bytes());
+}
+
+fn put_args(buf: &mut Vec<u8>, args: &[(&'static str, Arg<'_>)], snapshots: bool) {
+    buf.push(args.len() as u8);
+    for &(name, arg) in args {
+        put_str(buf, name);
+        match arg {
+            Arg::Int(v) => {
+                buf.push(0);
+                buf.extend_from_slice(&(v as i64).to_le_bytes());
+            }
+            Arg::F32(v) => {
+                buf.push(1);
+                buf.extend_from_slice(&v.to_le_bytes());
+            }
+            Arg::F64(v) => {
+                buf.push(2);
+                buf.extend_from_slice(&v.to_le_bytes());
+            }
+            Arg::F32s(v) => {
+                buf.push(3);
+                buf.extend_from_slice(&(v.len() as u64).to_le_bytes());
+                if snapshots {
+                    v.iter().for_each(|x| buf.extend_from_slice(&x.to_le_bytes()));
+                }
+            }
+            Arg::F64s(v) => {
+                buf.push(4);
+                buf.extend_from_slice(&(v.len() as u64).to_le_bytes());
+                if snapshots {
+                    v.iter().for_each(|x| buf.extend_from_slice(&x.to_le_bytes()));
+                }
+            }
+        }
+    }
+}
+
+/// Owned argument or output of a recorded call.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(isize),
+    F32(f32),
+    F64(f64),
+    F32s(Vec<f32>),
+    F64s(Vec<f64>),
+}
+
+/// One call read back from a trace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedCall {
+    pub routine: String,
+    /// Whether the input vectors and the outputs were recorded; without, vectors are filled with synthetic data.
+    pub snapshot: bool,
+    /// Instruction set of the context the call ran under.
+    pub isa: Isa,
+    /// Whether that context asked for reproducible reductions.
+    pub reproducible: bool,
+    pub args: Vec<(String, Value)>,
+    pub outputs: Vec<(String, Value)>,
+}
+
+/// An output of a replayed call that differs from the recorded one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    /// Position of the call in the trace.
+    pub call: usize,
+    pub routine: String,
+    pub output: String,
+    /// First differing element, `0` for a scalar output.
+    pub index: usize,
+    pub expected: f64,
+    pub actual: f64,
+}
+
+/// Outcome of `replay`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReplayReport {
+    /// Calls re-executed.
+    pub calls: usize,
+    /// Calls whose outputs were recorded and compared.
+    pub compared: usize,
+    /// Routines the current build does not know, skipped.
+    pub skipped: Vec<String>,
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl ReplayReport {
+    /// Whether every compared output matched bit for bit.
+    pub fn is_ok(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+struct Decoder<R> {
+    input: R,
+}
+
+impl<R: Read> Decoder<R> {
+    fn bytes<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let mut b = [0; N];
+        self.input.read_exact(&mut b)?;
+        Ok(b)
+    }
+
+    fn str(&mut self) -> io::Result<String> {
+        let mut b = vec![0; self.bytes::<1>()?[0] as usize];
+        self.input.read_exact(&mut b)?;
+        String::from_utf8(b).map_err(|_| invalid("routine or argument name is not UTF-8"))
+    }
+
+    /// vector length, bounded when the data is synthesised rather than read from the trace
+    fn len(&mut self, snapshot: bool) -> io::Result<usize> {
+        match usize::try_from(u64::from_le_bytes(self.bytes()?)) {
+            Ok(len) if snapshot || len <= MAX_SYNTHETIC_LEN => Ok(len),
+            _ => Err(invalid("vector too long")),
+        }
+    }
+
+    fn args(&mut self, snapshot: bool) -> io::Result<Vec<(String, Value)>> {
+        let count = self.bytes::<1>()?[0];
+        let mut args = Vec::with_capacity(count as usize);
+        for _ in 0 .. count {
+            let name = self.str()?;
+            let value = match self.bytes::<1>()?[0] {
+                0 => Value::Int(i64::from_le_bytes(self.bytes()?) as isize),
+                1 => Value::F32(f32::from_le_bytes(self.bytes()?)),
+                2 => Value::F64(f64::from_le_bytes(self.bytes()?)),
+                3 => {
+                    let len = self.len(snapshot)?;
+                    Value::F32s(if snapshot {
+                        (0 .. len).map(|_| self.bytes().map(f32::from_le_bytes)).collect::<io::Result<_>>()?
+                    } else {
+                        (0 .. len).map(|i| synthetic(i) as f32).collect()
+                    })
+                }
+                4 => {
+                    let len = self.len(snapshot)?;
+                    Value::F64s(if snapshot {
+                        (0 .. len).map(|_| self.bytes().map(f64::from_le_bytes)).collect::<io::Result<_>>()?
+                    } else {
+                        (0 .. len).map(synthetic).collect()
+                    })
+                }
+                _ => return Err(invalid("unknown argument tag")),
+            };
+            args.push((name, value));
+        }
+        Ok(args)
+    }
+}
+
+/// deterministic stand-in for vector data that was not recorded
+fn synthetic(i: usize) -> f64 {
+    ((i * 7919) % 17) as f64 / 8.0 - 1.0
+}
+
+/// Reads every call of a trace written by `start_recording`.
+pub fn read_trace<R: Read>(reader: R) -> io::Result<Vec<RecordedCall>> {
+    let mut d = Decoder { input: BufReader::new(reader) };
+    if &d.bytes::<8>()? != MAGIC {
+        return Err(invalid("not an rsblas trace"));
+    }
+    let mut calls = Vec::new();
+    loop {
+        let routine = match d.str() {
+            Ok(routine) => routine,
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(calls),
+            Err(e) => return Err(e),
+        };
+        let [snapshot, isa, reproducible] = d.bytes::<3>()?;
+        let snapshot = snapshot != 0;
+        let isa = match isa {
+            0 => Isa::Scalar,
+            1 => Isa::Sse2,
+            2 => Isa::Avx2,
+            3 => Isa::Fma,
+            _ => return Err(invalid("unknown instruction set")),
+        };
+        let args = d.args(snapshot)?;
+        let outputs = d.args(snapshot)?;
+        calls.push(RecordedCall { routine, snapshot, isa, reproducible: reproducible != 0, args, outputs });
+    }
+}
+
+impl RecordedCall {
+    fn arg(&self, i: usize) -> &Value {
+        &self.args[i].1
+    }
+
+    fn int(&self, i: usize) -> isize {
+        match *self.arg(i) { Value::Int(v) => v, _ => 0 }
+    }
+
+    fn f32(&self, i: usize) -> f32 {
+        match *self.arg(i) { Value::F32(v) => v, _ => 0.0 }
+    }
+
+    fn f64(&self, i: usize) -> f64 {
+        match *self.arg(i) { Value::F64(v) => v, _ => 0.0 }
+    }
+
+    fn f32s(&self, i: usize) -> Vec<f32> {
+        match self.arg(i) { Value::F32s(v) => v.clone(), _ => Vec::new() }
+    }
+
+    fn f64s(&self, i: usize) -> Vec<f64> {
+        match self.arg(i) { Value::F64s(v) => v.clone(), _ => Vec::new() }
+    }
+
+    /// runs the call on the current build, `None` for an unknown routine or a malformed argument list
+    pub fn execute(&self) -> Option<Vec<(String, Value)>> {
+        use crate::*;
+        let out = |name: &str, v: Value| (name.to_string(), v);
+        let expected_args = match &self.routine[..] {
+            "sasum" | "dasum" | "snrm2" | "dnrm2" => 3,
+            "scopy" | "dcopy" | "sdot" | "ddot" | "sswap" | "dswap" => 5,
+            "saxpy" | "daxpy" => 6,
+            "sscal" | "dscal" => 4,
+            "srot" | "drot" => 7,
+            "srotg" | "drotg" => 2,
+            _ => return None,
+        };
+        if self.args.len() != expected_args {
+            return None;
+        }
+        let n = self.int(0);
+        Some(match &self.routine[..] {
+            "sasum" => vec![out("result", Value::F32(sasum(n, &self.f32s(1), self.int(2)).unwrap_or_default()))],
+            "dasum" => vec![out("result", Value::F64(dasum(n, &self.f64s(1), self.int(2)).unwrap_or_default()))],
+            "snrm2" => vec![out("result", Value::F32(snrm2(n, &self.f32s(1), self.int(2)).unwrap_or_default()))],
+            "dnrm2" => vec![out("result", Value::F64(dnrm2(n, &self.f64s(1), self.int(2)).unwrap_or_default()))],
+            "sdot" => vec![out("result", Value::F32(sdot(n, &self.f32s(1), self.int(2), &self.f32s(3), self.int(4)).unwrap_or_default()))],
+            "ddot" => vec![out("result", Value::F64(ddot(n, &self.f64s(1), self.int(2), &self.f64s(3), self.int(4)).unwrap_or_default()))],
+            "saxpy" => {
+                let mut y = self.f32s(4);
+                let _ = saxpy(n, self.f32(1), &self.f32s(2), self.int(3), &mut y, self.int(5));
+                vec![out("y", Value::F32s(y))]
+            }
+            "daxpy" => {
+                let mut y = self.f64s(4);
+                let _ = daxpy(n, self.f64(1), &self.f64s(2), self.int(3), &mut y, self.int(5));
+                vec![out("y", Value::F64s(y))]
+            }
+            "scopy" => {
+                let mut y = self.f32s(3);
+                let _ = scopy(n, &self.f32s(1), self.int(2), &mut y, self.int(4));
+                vec![out("y", Value::F32s(y))]
+            }
+            "dcopy" => {
+                let mut y = self.f64s(3);
+                let _ = dcopy(n, &self.f64s(1), self.int(2), &mut y, self.int(4));
+                vec![out("y", Value::F64s(y))]
+            }
+            "sscal" => {
+                let mut x = self.f32s(2);
+                let _ = sscal(n, self.f32(1), &mut x, self.int(3));
+                vec![out("x", Value::F32s(x))]
+            }
+            "dscal" => {
+                let mut x = self.f64s(2);
+                let _ = dscal(n, self.f64(1), &mut x, self.int(3));
+                vec![out("x", Value::F64s(x))]
+            }
+            "sswap" | "srot" => {
+                let (mut x, mut y) = (self.f32s(1), self.f32s(3));
+                let _ = if self.routine == "sswap" {
+                    sswap(n, &mut x, self.int(2), &mut y, self.int(4))
+                } else {
+                    srot(n, &mut x, self.int(2), &mut y, self.int(4), self.f32(5), self.f32(6))
+                };
+                vec![out("x", Value::F32s(x)), out("y", Value::F32s(y))]
+            }
+            "dswap" | "drot" => {
+                let (mut x, mut y) = (self.f64s(1), self.f64s(3));
+                let _ = if self.routine == "dswap" {
+                    dswap(n, &mut x, self.int(2), &mut y, self.int(4))
+                } else {
+                    drot(n, &mut x, self.int(2), &mut y, self.int(4), self.f64(5), self.f64(6))
+                };
+                vec![out("x", Value::F64s(x)), out("y", Value::F64s(y))]
+            }
+            "srotg" => {
+                let (mut a, mut b, mut c, mut s) = (self.f32(0), self.f32(1), 0.0, 0.0);
+                let _ = srotg(&mut a, &mut b, &mut c, &mut s);
+                [("a", a), ("b", b), ("c", c), ("s", s)].iter().map(|&(k, v)| out(k, Value::F32(v))).collect()
+            }
+            "drotg" => {
+                let (mut a, mut b, mut c, mut s) = (self.f64(0), self.f64(1), 0.0, 0.0);
+                let _ = drotg(&mut a, &mut b, &mut c, &mut s);
+                [("a", a), ("b", b), ("c", c), ("s", s)].iter().map(|&(k, v)| out(k, Value::F64(v))).collect()
+            }
+            _ => return None,
+        })
+    }
+}
+
+/// first element where two outputs differ bit for bit, with both values
+fn first_difference(expected: &Value, actual: &Value) -> Option<(usize, f64, f64)> {
+    fn scan<T: Copy + Into<f64>>(e: &[T], a: &[T], same: impl Fn(T, T) -> bool) -> Option<(usize, f64, f64)> {
+        if e.len() != a.len() {
+            return Some((e.len().min(a.len()), e.len() as f64, a.len() as f64));
+        }
+        e.iter().zip(a).position(|(&x, &y)| !same(x, y)).map(|i| (i, e[i].into(), a[i].into()))
+    }
+    match (expected, actual) {
+        (Value::Int(e), Value::Int(a)) => if e != a { Some((0, *e as f64, *a as f64)) } else { None },
+        (Value::F32(e), Value::F32(a)) => scan(&[*e], &[*a], |x, y| x.to_bits() == y.to_bits()),
+        (Value::F64(e), Value::F64(a)) => scan(&[*e], &[*a], |x, y| x.to_bits() == y.to_bits()),
+        (Value::F32s(e), Value::F32s(a)) => scan(e, a, |x, y| x.to_bits() == y.to_bits()),
+        (Value::F64s(e), Value::F64s(a)) => scan(e, a, |x, y| x.to_bits() == y.to_bits()),
+        _ => Some((0, f64::NAN, f64::NAN)),
+    }
+}
+
+/// Re-executes every call of a trace on the current build and compares the outputs with the recorded ones,
+/// bit for bit. Calls recorded without snapshots are executed on synthetic data and not compared.
+///
+/// Each call runs under the current context with the instruction set and reproducibility it was recorded with,
+/// the instruction set capped at what the CPU supports. Calls made while replaying are not themselves recorded.
+pub fn replay<R: Read>(reader: R) -> io::Result<ReplayReport> {
+    Ok(replay_calls(&read_trace(reader)?))
+}
+
+/// `replay` of calls already read with `read_trace`.
+pub fn replay_calls(calls: &[RecordedCall]) -> ReplayReport {
+    let _replaying = Replaying::start();
+    let current = Context::current();
+    let mut report = ReplayReport::default();
+    for (i, call) in calls.iter().enumerate() {
+        let ctx = current.clone().with_isa(call.isa).with_reproducible(call.reproducible);
+        let actual = match ctx.install(|| call.execute()) {
+            Some(actual) => actual,
+            None => {
+                report.skipped.push(call.routine.clone());
+                continue;
+            }
+        };
+        report.calls += 1;
+        if !call.snapshot {
+            continue;
+        }
+        report.compared += 1;
+        for ((name, expected), (_, actual)) in call.outputs.iter().zip(&actual) {
+            if let Some((index, expected, actual)) = first_difference(expected, actual) {
+                let routine = call.routine.clone();
+                report.mismatches.push(Mismatch { call: i, routine, output: name.clone(), index, expected, actual });
+            }
+        }
+    }
+    report
+}
+
+/// `replay` of the trace file at `path`.
+pub fn replay_file<P: AsRef<Path>>(path: P) -> io::Result<ReplayReport> {
+    replay(File::open(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// shared in-memory trace
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn recorded_calls_replay_identically() {
+        let trace = Shared::default();
+        start_recording(trace.clone(), true).unwrap();
+        let mut y = [1.0_f64, 2.0, 3.0];
+        crate::daxpy(3, 0.5, &[2.0, 4.0, 6.0], 1, &mut y, -1).unwrap();
+        let dot = crate::sdot(2, &[1.0, 2.0], 1, &[3.0, 4.0], 1).unwrap();
+        let (mut a, mut b, mut c, mut s) = (3.0_f32, 4.0, 0.0, 0.0);
+        crate::srotg(&mut a, &mut b, &mut c, &mut s).unwrap();
+        let x: Vec<f64> = (0 .. 37).map(|i| 1.0 + i as f64 / 7.0).collect();
+        let scalar = Context::new().with_isa(Isa::Scalar).install(|| crate::ddot(37, &x, 1, &x, 1)).unwrap();
+        stop_recording().unwrap();
+
+        let bytes = trace.0.lock().unwrap().clone();
+        // other tests may run concurrently and be recorded as well, keep the calls made here
+        let calls: Vec<_> = read_trace(&bytes[..]).unwrap().into_iter().filter(|c| match &c.routine[..] {
+            "daxpy" => c.args[1].1 == Value::F64(0.5),
+            "sdot" => c.outputs == [("result".to_string(), Value::F32(dot))],
+            "srotg" => c.args[0].1 == Value::F32(3.0) && c.args[1].1 == Value::F32(4.0),
+            "ddot" => c.args[0].1 == Value::Int(37) && c.isa == Isa::Scalar,
+            _ => false,
+        }).collect();
+        let daxpy = calls.iter().find(|c| c.routine == "daxpy").unwrap();
+        assert_eq!(daxpy.outputs, [("y".to_string(), Value::F64s(y.to_vec()))]);
+        let ddot = calls.iter().find(|c| c.routine == "ddot").unwrap();
+        assert_eq!(ddot.outputs, [("result".to_string(), Value::F64(scalar))]);
+
+        // the ddot replays on the scalar kernels it was recorded with
+        let report = replay_calls(&calls);
+        assert!(report.is_ok(), "{:?}", report.mismatches);
+        assert_eq!(report.compared, 4);
+
+        // a tampered output is caught at the right element
+        let mut tampered = daxpy.clone();
+        tampered.outputs[0].1 = Value::F64s(vec![y[0], y[1] + 1.0, y[2]]);
+        let actual = tampered.execute().unwrap();
+        assert_eq!(first_difference(&tampered.outputs[0].1, &actual[0].1), Some((1, y[1] + 1.0, y[1])));
+    }
+
+    #[test]
+    fn corrupt_vector_length_is_rejected() {
+        let mut bytes = MAGIC.to_vec();
+        put_str(&mut bytes, "dasum");
+        bytes.extend_from_slice(&[0, 0, 0]);
+        bytes.push(1);
+        put_str(&mut bytes, "x");
+        bytes.push(4);
+        bytes.extend_from_slice(&(1_u64 << 40).to_le_bytes());
+        bytes.push(0);
+        let err = read_trace(&bytes[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "vector too long");
+    }
+}
@@ -1,204 +1,280 @@
-use std::ops::{Add, Sub, Mul};
-use crate::utils::{check_inc, get_first_index};
-
-trait RotQuickReturn {
-    fn quick_return(c: Self, s: Self) -> bool;
-}
-
-impl RotQuickReturn for f32 {
-    fn quick_return(c: Self, s: Self) -> bool {
-        c == 1.0f32 && s == 0.0f32
-    }
-}
-
-impl RotQuickReturn for f64 {
-    fn quick_return(c: Self, s: Self) -> bool {
-        c == 1.0f64 && s == 0.0f64
-    }
-}
-
-fn rot<T>(n: isize, x: &mut [T], incx: isize, y: &mut [T], incy: isize, c: T, s: T) -> bool
-where T: Default + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Copy + RotQuickReturn,
-{
-    if check_inc(n, x, incx) != true || check_inc(n, y, incy) != true {
-        return false;
-    }
-
-    if <T as RotQuickReturn>::quick_return(c, s) {
-        return true;
-    }
-
-    let n_usize = n as usize;
-    if incx == 1 && incy == 1 {
-        for i in 0 .. n_usize {
-            let temp = c * x[i] + s * y[i];
-            y[i] = c * y[i] - s * x[i];
-            x[i] = temp;
-        }
-        return true;
-    }
-
-    let incx_abs: usize = if incx > 0 {incx as usize} else {(-incx) as usize};
-    let mut ix: usize = get_first_index(n_usize, incx);
-    
-    let incy_abs: usize = if incy > 0 {incy as usize} else {(-incy) as usize};
-    let mut iy: usize = get_first_index(n_usize, incy);
-
-    for _ in 0 .. n_usize {
-        let temp = c * x[ix] + s * y[iy];
-        y[iy] = c * y[iy] - s * x[ix];
-        x[ix] = temp;
-        
-        ix = if incx > 0 {
-            ix + incx_abs
-        } else {
-            ix - incx_abs
-        };
-        iy = if incy > 0 {
-            iy + incy_abs
-        } else {
-            iy - incy_abs
-        };
-    }
-
-    true
-}
-
-
-/// Applies an `f32` plane rotation to 2 _n_-element `f32` vectors: `x` and `y`, with respective strides `incx` and `incy`.
-/// 
-/// <link rel="stylesheet"
-/// href="https://cdn.jsdelivr.net/npm/katex@0.10.0/dist/katex.min.css"
-/// integrity="sha384-9eLZqc9ds8eNjO3TmqPeYcDj8n+Qfa4nuSiGYa6DjLNcv9BtN69ZIulL9+8CqC9Y"
-/// crossorigin="anonymous">
-/// <script src="https://cdn.jsdelivr.net/npm/katex@0.10.0/dist/katex.min.js"
-///   integrity="sha384-K3vbOmF2BtaVai+Qk37uypf7VrgBubhQreNQe9aGsz9lB63dIFiQVlJbr92dw2Lx"
-///   crossorigin="anonymous"></script>
-/// <script src="https://cdn.jsdelivr.net/npm/katex@0.10.0/dist/contrib/auto-render.min.js"
-///   integrity="sha384-kmZOZB5ObwgQnS/DuDg6TScgOiWWBiVt0plIRkZCmE6rDZGrEOQeHM5PcHi+nyqe"
-///   crossorigin="anonymous"></script>
-/// <script>
-/// document.addEventListener("DOMContentLoaded", function() {
-///   renderMathInElement(document.body, {
-///       delimiters: [
-///           {left: "$$", right: "$$", display: true},
-///           {left: "\\(", right: "\\)", display: false},
-///           {left: "$", right: "$", display: false},
-///           {left: "\\[", right: "\\]", display: true}
-///       ]
-///   });
-/// });
-/// </script>
-/// 
-/// $$ \\begin{bmatrix}
-///      x^\\prime \\\\ y^\\prime
-///    \\end{bmatrix} \leftarrow
-///    \\begin{bmatrix}
-///      c & s \\\\
-///     -s & c
-///    \\end{bmatrix}
-///    \\cdot
-///    \\begin{bmatrix}
-///      x \\\\ y
-///    \\end{bmatrix}
-/// $$
-/// 
-/// - `n: isize`<br>Number of planar points, in `x` and `y`, to be rotated.
-///   - _on entry_:. if `n = 0`, this function returns immediately.
-/// 
-/// - `x: &mut [f32]`<br>Array of dimension at least `(n - 1) * incx + 1`.
-///    - _on entry_: the _n_-elements are `x[i * incx] for i = 0..n`
-///    - _on exit_: the rotated values are updated in-place.
-/// 
-/// - `incx: isize`<br>Increment between elements of `x` as input and output.
-///   - _on entry_: if `incx = 0`, this function returns immediately.
-/// 
-/// - `y: &mut [f32]`<br>Array of dimension at least `(n - 1) * incy + 1`.
-///   - _on entry_: the _n_-elements are `y[i * incy] for i = 0..n`
-///   - _on exit_: the rotated values are updated in-place.
-/// 
-/// - `incy: isize`<br>Increment between elements of `y` as input and output.
-///   - _on entry_: if `incy = 0`, this function returns immediately.
-/// 
-/// - `c: f32`<br>Cosine of the angle of rotation.
-/// l
-/// - `s: f32`<br>Sine of the angle of rotation.
-/// 
-/// If coefficients `c` and `s` satisfy $c^2+s^2=1$, the rotation matrix is orthogonal, and the transformation is called a Givens plane rotation.
-/// 
-/// If `c = 1` and `s = 0`, this function returns immediately.
-/// 
-/// Reference:
-/// 1. [https://www.hpc.nec/documents/sdk/SDK_NLC/UsersGuide/man/srot.html](https://www.hpc.nec/documents/sdk/SDK_NLC/UsersGuide/man/srot.html)
-pub fn srot(n: isize, x: &mut [f32], incx: isize, y: &mut [f32], incy: isize, c: f32, s: f32) -> bool {
-    rot::<f32>(n, x, incx, y, incy, c, s)
-}
-
-/// Applies an `f64` plane rotation to 2 _n_-element `f64` vectors: `x` and `y`, with respective strides `incx` and `incy`.
-/// 
-/// <link rel="stylesheet"
-/// href="https://cdn.jsdelivr.net/npm/katex@0.10.0/dist/katex.min.css"
-/// integrity="sha384-9eLZqc9ds8eNjO3TmqPeYcDj8n+Qfa4nuSiGYa6DjLNcv9BtN69ZIulL9+8CqC9Y"
-/// crossorigin="anonymous">
-/// <script src="https://cdn.jsdelivr.net/npm/katex@0.10.0/dist/katex.min.js"
-///   integrity="sha384-K3vbOmF2BtaVai+Qk37uypf7VrgBubhQreNQe9aGsz9lB63dIFiQVlJbr92dw2Lx"
-///   crossorigin="anonymous"></script>
-/// <script src="https://cdn.jsdelivr.net/npm/katex@0.10.0/dist/contrib/auto-render.min.js"
-///   integrity="sha384-kmZOZB5ObwgQnS/DuDg6TScgOiWWBiVt0plIRkZCmE6rDZGrEOQeHM5PcHi+nyqe"
-///   crossorigin="anonymous"></script>
-/// <script>
-/// document.addEventListener("DOMContentLoaded", function() {
-///   renderMathInElement(document.body, {
-///       delimiters: [
-///           {left: "$$", right: "$$", display: true},
-///           {left: "\\(", right: "\\)", display: false},
-///           {left: "$", right: "$", display: false},
-///           {left: "\\[", right: "\\]", display: true}
-///       ]
-///   });
-/// });
-/// </script>
-/// 
-/// $$ \\begin{bmatrix}
-///      x^\\prime \\\\ y^\\prime
-///    \\end{bmatrix} \leftarrow
-///    \\begin{bmatrix}
-///      c & s \\\\
-///     -s & c
-///    \\end{bmatrix}
-///    \\cdot
-///    \\begin{bmatrix}
-///      x \\\\ y
-///    \\end{bmatrix}
-/// $$
-/// 
-/// - `n: isize`<br>Number of planar points, in `x` and `y`, to be rotated.
-///   - _on entry_:. if `n = 0`, this function returns immediately.
-/// 
-/// - `x: &mut [f64]`<br>Array of dimension at least `(n - 1) * incx + 1`.
-///    - _on entry_: the _n_-elements are `x[i * incx] for i = 0..n`
-///    - _on exit_: the rotated values are updated in-place.
-/// 
-/// - `incx: isize`<br>Increment between elements of `x` as input and output.
-///   - _on entry_: if `incx = 0`, this function returns immediately.
-/// 
-/// - `y: &mut [f64]`<br>Array of dimension at least `(n - 1) * incy + 1`.
-///   - _on entry_: the _n_-elements are `y[i * incy] for i = 0..n`
-///   - _on exit_: the rotated values are updated in-place.
-/// 
-/// - `incy: isize`<br>Increment between elements of `y` as input and output.
-///   - _on entry_: if `incy = 0`, this function returns immediately.
-/// 
-/// - `c: f64`<br>Cosine of the angle of rotation.
-/// 
-/// - `s: f64`<br>Sine of the angle of rotation.
-/// 
-/// If coefficients `c` and `s` satisfy $c^2+s^2=1$, the rotation matrix is orthogonal, and the transformation is called a Givens plane rotation.
-/// 
-/// If `c = 1` and `s = 0`, this function returns immediately.
-/// 
-/// Reference:
-/// 1. [https://www.hpc.nec/documents/sdk/SDK_NLC/UsersGuide/man/drot.html](https://www.hpc.nec/documents/sdk/SDK_NLC/UsersGuide/man/drot.html)
-pub fn drot(n: isize, x: &mut [f64], incx: isize, y: &mut [f64], incy: isize, c: f64, s: f64) -> bool {
-    rot::<f64>(n, x, incx, y, incy, c, s)
-}
\ No newline at end of file
+use crate::error::BlasError;
+use crate::scalar::Scalar;
+use crate::xerbla::report;
+use crate::trace::{self, Arg};
+use crate::view::{VecMut, Strided, check_len, check_within};
+use crate::utils::{check_n, check_inc};
+use crate::strided::walk2;
+use crate::dispatch;
+
+/// applies the plane rotation `(c, s)` to the vectors `x` and `y`, for any `Scalar` type, see `srot`
+pub fn rot<T>(n: isize, x: &mut [T], incx: isize, y: &mut [T], incy: isize, c: T, s: T) -> Result<(), BlasError>
+where T: Scalar,
+{
+    let n_usize = check_n(n, 1)?;
+    check_inc(n_usize, x, incx, 2, "x", "incx")?;
+    check_inc(n_usize, y, incy, 4, "y", "incy")?;
+
+    // SAFETY: both slices were checked to hold `n` elements at their increments, and cannot alias each other.
+    unsafe { rot_unchecked(n_usize, x.as_mut_ptr(), incx, y.as_mut_ptr(), incy, c, s) };
+    Ok(())
+}
+
+/// `rot` on raw pointers, validated by debug assertions only, like CBLAS `cblas_?rot`
+///
+/// As in BLAS, a negative increment walks the array from its end: element `i` of `x` is `x[(n - 1 - i) * |incx|]`.
+///
+/// # Safety
+///
+/// `x` must be valid for reads and writes of `(n - 1) * |incx| + 1` elements, `y` of `(n - 1) * |incy| + 1`
+/// elements, and no element may be reached through both `x` and `y`.
+pub unsafe fn rot_unchecked<T>(n: usize, x: *mut T, incx: isize, y: *mut T, incy: isize, c: T, s: T)
+where T: Scalar,
+{
+    debug_assert!(n == 0 || (!x.is_null() && !y.is_null()));
+    debug_assert!(n <= 1 || (incx != 0 && incy != 0), "increments must be nonzero");
+    if n == 0 || (c == T::one() && s == T::zero()) {
+        return;
+    }
+
+    if incx == 1 && incy == 1 {
+        if let Some(kernels) = dispatch::kernels::<T>() {
+            return (kernels.rot)(n, x, y, c, s);
+        }
+    }
+    walk2(n, x, incx, y, incy, |px, py| {
+        let temp = c * *px + s * *py;
+        *py = c * *py - s * *px;
+        *px = temp;
+    });
+}
+
+
+/// Applies an `f32` plane rotation to 2 _n_-element `f32` vectors: `x` and `y`, with respective strides `incx` and `incy`.
+/// 
+/// <link rel="stylesheet"
+/// href="https://cdn.jsdelivr.net/npm/katex@0.10.0/dist/katex.min.css"
+/// integrity="sha384-9eLZqc9ds8eNjO3TmqPeYcDj8n+Qfa4nuSiGYa6DjLNcv9BtN69ZIulL9+8CqC9Y"
+/// crossorigin="anonymous">
+/// <script src="https://cdn.jsdelivr.net/npm/katex@0.10.0/dist/katex.min.js"
+///   integrity="sha384-K3vbOmF2BtaVai+Qk37uypf7VrgBubhQreNQe9aGsz9lB63dIFiQVlJbr92dw2Lx"
+///   crossorigin="anonymous"></script>
+/// <script src="https://cdn.jsdelivr.net/npm/katex@0.10.0/dist/contrib/auto-render.min.js"
+///   integrity="sha384-kmZOZB5ObwgQnS/DuDg6TScgOiWWBiVt0plIRkZCmE6rDZGrEOQeHM5PcHi+nyqe"
+///   crossorigin="anonymous"></script>
+/// <script>
+/// document.addEventListener("DOMContentLoaded", function() {
+///   renderMathInElement(document.body, {
+///       delimiters: [
+///           {left: "$$", right: "$$", display: true},
+///           {left: "\\(", right: "\\)", display: false},
+///           {left: "$", right: "$", display: false},
+///           {left: "\\[", right: "\\]", display: true}
+///       ]
+///   });
+/// });
+/// </script>
+/// 
+/// $$ \\begin{bmatrix}
+///      x^\\prime \\\\ y^\\prime
+///    \\end{bmatrix} \leftarrow
+///    \\begin{bmatrix}
+///      c & s \\\\
+///     -s & c
+///    \\end{bmatrix}
+///    \\cdot
+///    \\begin{bmatrix}
+///      x \\\\ y
+///    \\end{bmatrix}
+/// $$
+/// 
+/// - `n: isize`<br>Number of planar points, in `x` and `y`, to be rotated.
+///   - _on entry_: if `n = 0`, this function returns immediately; if `n < 0`, it returns `BlasError::InvalidDimension`.
+/// 
+/// - `x: &mut [f32]`<br>Array of dimension at least `(n - 1) * incx + 1`.
+///    - _on entry_: the _n_-elements are `x[i * incx] for i = 0..n`
+///    - _on exit_: the rotated values are updated in-place.
+/// 
+/// - `incx: isize`<br>Increment between elements of `x` as input and output.
+///   - _on entry_: if `incx = 0`, this function returns `BlasError::InvalidIncrement`.
+/// 
+/// - `y: &mut [f32]`<br>Array of dimension at least `(n - 1) * incy + 1`.
+///   - _on entry_: the _n_-elements are `y[i * incy] for i = 0..n`
+///   - _on exit_: the rotated values are updated in-place.
+/// 
+/// - `incy: isize`<br>Increment between elements of `y` as input and output.
+///   - _on entry_: if `incy = 0`, this function returns `BlasError::InvalidIncrement`.
+/// 
+/// - `c: f32`<br>Cosine of the angle of rotation.
+/// 
+/// - `s: f32`<br>Sine of the angle of rotation.
+/// 
+/// If coefficients `c` and `s` satisfy $c^2+s^2=1$, the rotation matrix is orthogonal, and the transformation is called a Givens plane rotation.
+/// 
+/// If `c = 1` and `s = 0`, this function returns immediately.
+/// 
+/// If `x` or `y` is shorter than its dimension requires, this function returns `BlasError::SliceTooShort`.
+/// 
+/// Reference:
+/// 1. [https://www.hpc.nec/documents/sdk/SDK_NLC/UsersGuide/man/srot.html](https://www.hpc.nec/documents/sdk/SDK_NLC/UsersGuide/man/srot.html)
+pub fn srot(n: isize, x: &mut [f32], incx: isize, y: &mut [f32], incy: isize, c: f32, s: f32) -> Result<(), BlasError> {
+    let call = trace::begin("srot", &[
+        ("n", Arg::Int(n)), ("x", Arg::F32s(x)), ("incx", Arg::Int(incx)), ("y", Arg::F32s(y)),
+        ("incy", Arg::Int(incy)), ("c", Arg::F32(c)), ("s", Arg::F32(s)),
+    ]);
+    let result = rot::<f32>(n, x, incx, y, incy, c, s).map_err(|e| report("srot", e));
+    trace::end(call, result.is_ok(), &[("x", Arg::F32s(x)), ("y", Arg::F32s(y))]);
+    result
+}
+
+/// Applies an `f64` plane rotation to 2 _n_-element `f64` vectors: `x` and `y`, with respective strides `incx` and `incy`.
+/// 
+/// <link rel="stylesheet"
+/// href="https://cdn.jsdelivr.net/npm/katex@0.10.0/dist/katex.min.css"
+/// integrity="sha384-9eLZqc9ds8eNjO3TmqPeYcDj8n+Qfa4nuSiGYa6DjLNcv9BtN69ZIulL9+8CqC9Y"
+/// crossorigin="anonymous">
+/// <script src="https://cdn.jsdelivr.net/npm/katex@0.10.0/dist/katex.min.js"
+///   integrity="sha384-K3vbOmF2BtaVai+Qk37uypf7VrgBubhQreNQe9aGsz9lB63dIFiQVlJbr92dw2Lx"
+///   crossorigin="anonymous"></script>
+/// <script src="https://cdn.jsdelivr.net/npm/katex@0.10.0/dist/contrib/auto-render.min.js"
+///   integrity="sha384-kmZOZB5ObwgQnS/DuDg6TScgOiWWBiVt0plIRkZCmE6rDZGrEOQeHM5PcHi+nyqe"
+///   crossorigin="anonymous"></script>
+/// <script>
+/// document.addEventListener("DOMContentLoaded", function() {
+///   renderMathInElement(document.body, {
+///       delimiters: [
+///           {left: "$$", right: "$$", display: true},
+///           {left: "\\(", right: "\\)", display: false},
+///           {left: "$", right: "$", display: false},
+///           {left: "\\[", right: "\\]", display: true}
+///       ]
+///   });
+/// });
+/// </script>
+/// 
+/// $$ \\begin{bmatrix}
+///      x^\\prime \\\\ y^\\prime
+///    \\end{bmatrix} \leftarrow
+///    \\begin{bmatrix}
+///      c & s \\\\
+///     -s & c
+///    \\end{bmatrix}
+///    \\cdot
+///    \\begin{bmatrix}
+///      x \\\\ y
+///    \\end{bmatrix}
+/// $$
+/// 
+/// - `n: isize`<br>Number of planar points, in `x` and `y`, to be rotated.
+///   - _on entry_: if `n = 0`, this function returns immediately; if `n < 0`, it returns `BlasError::InvalidDimension`.
+/// 
+/// - `x: &mut [f64]`<br>Array of dimension at least `(n - 1) * incx + 1`.
+///    - _on entry_: the _n_-elements are `x[i * incx] for i = 0..n`
+///    - _on exit_: the rotated values are updated in-place.
+/// 
+/// - `incx: isize`<br>Increment between elements of `x` as input and output.
+///   - _on entry_: if `incx = 0`, this function returns `BlasError::InvalidIncrement`.
+/// 
+/// - `y: &mut [f64]`<br>Array of dimension at least `(n - 1) * incy + 1`.
+///   - _on entry_: the _n_-elements are `y[i * incy] for i = 0..n`
+///   - _on exit_: the rotated values are updated in-place.
+/// 
+/// - `incy: isize`<br>Increment between elements of `y` as input and output.
+///   - _on entry_: if `incy = 0`, this function returns `BlasError::InvalidIncrement`.
+/// 
+/// - `c: f64`<br>Cosine of the angle of rotation.
+/// 
+/// - `s: f64`<br>Sine of the angle of rotation.
+/// 
+/// If coefficients `c` and `s` satisfy $c^2+s^2=1$, the rotation matrix is orthogonal, and the transformation is called a Givens plane rotation.
+/// 
+/// If `c = 1` and `s = 0`, this function returns immediately.
+/// 
+/// If `x` or `y` is shorter than its dimension requires, this function returns `BlasError::SliceTooShort`.
+/// 
+/// Reference:
+/// 1. [https://www.hpc.nec/documents/sdk/SDK_NLC/UsersGuide/man/drot.html](https://www.hpc.nec/documents/sdk/SDK_NLC/UsersGuide/man/drot.html)
+pub fn drot(n: isize, x: &mut [f64], incx: isize, y: &mut [f64], incy: isize, c: f64, s: f64) -> Result<(), BlasError> {
+    let call = trace::begin("drot", &[
+        ("n", Arg::Int(n)), ("x", Arg::F64s(x)), ("incx", Arg::Int(incx)), ("y", Arg::F64s(y)),
+        ("incy", Arg::Int(incy)), ("c", Arg::F64(c)), ("s", Arg::F64(s)),
+    ]);
+    let result = rot::<f64>(n, x, incx, y, incy, c, s).map_err(|e| report("drot", e));
+    trace::end(call, result.is_ok(), &[("x", Arg::F64s(x)), ("y", Arg::F64s(y))]);
+    result
+}
+
+/// `srot` on raw pointers without bounds checks
+///
+/// # Safety
+///
+/// See `rot_unchecked`.
+pub unsafe fn srot_unchecked(n: usize, x: *mut f32, incx: isize, y: *mut f32, incy: isize, c: f32, s: f32) {
+    rot_unchecked::<f32>(n, x, incx, y, incy, c, s)
+}
+
+/// `drot` on raw pointers without bounds checks
+///
+/// # Safety
+///
+/// See `rot_unchecked`.
+pub unsafe fn drot_unchecked(n: usize, x: *mut f64, incx: isize, y: *mut f64, incy: isize, c: f64, s: f64) {
+    rot_unchecked::<f64>(n, x, incx, y, incy, c, s)
+}
+
+/// applies the plane rotation `(c, s)` to two vector views, for any `Scalar` type
+pub fn rot_view<T>(x: &mut VecMut<'_, T>, y: &mut VecMut<'_, T>, c: T, s: T) -> Result<(), BlasError>
+where T: Scalar,
+{
+    check_len(x.len(), y.len(), 2, "y")?;
+    let (n, x, incx) = x.as_blas();
+    let (_, y, incy) = y.as_blas();
+    rot(n, x, incx, y, incy, c, s)
+}
+
+/// `srot` on vector views
+pub fn srot_view(x: &mut VecMut<'_, f32>, y: &mut VecMut<'_, f32>, c: f32, s: f32) -> Result<(), BlasError> {
+    rot_view::<f32>(x, y, c, s).map_err(|e| report("srot_view", e))
+}
+
+/// `drot` on vector views
+pub fn drot_view(x: &mut VecMut<'_, f64>, y: &mut VecMut<'_, f64>, c: f64, s: f64) -> Result<(), BlasError> {
+    rot_view::<f64>(x, y, c, s).map_err(|e| report("drot_view", e))
+}
+
+/// rotates two non-overlapping vectors `x` and `y` stored in the same buffer
+pub(crate) fn rot_strided<T>(buf: &mut [T], x: Strided, y: Strided, c: T, s: T)
+where T: Scalar,
+{
+    debug_assert!(!x.overlaps(&y));
+    debug_assert!(x.bounds().1 <= buf.len() && y.bounds().1 <= buf.len());
+    let base = buf.as_mut_ptr();
+    // SAFETY: both vectors lie inside `buf` and do not share an element.
+    unsafe { rot_unchecked(x.len, base.add(x.bounds().0), x.stride, base.add(y.bounds().0), y.stride, c, s) };
+}
+
+/// applies the plane rotation `(c, s)` to two vectors of the same buffer, `x[i] = buf[offx + i * incx]` and
+/// `y[i] = buf[offy + i * incy]`, for any `Scalar` type
+///
+/// Typical use is rotating two rows or columns of one matrix. The strides may be negative, and `x` and `y`
+/// must not share an element, otherwise `BlasError::Overlap` is returned.
+pub fn rot_within<T>(n: isize, buf: &mut [T], offx: usize, incx: isize, offy: usize, incy: isize, c: T, s: T) -> Result<(), BlasError>
+where T: Scalar,
+{
+    let (x, y) = check_within(n, buf.len(), offx, incx, offy, incy)?;
+    if x.overlaps(&y) {
+        return Err(BlasError::Overlap { pos: 5, name: "offy" });
+    }
+    rot_strided(buf, x, y, c, s);
+    Ok(())
+}
+
+/// `srot` on two non-overlapping vectors of the same buffer, see `rot_within`
+pub fn srot_within(n: isize, buf: &mut [f32], offx: usize, incx: isize, offy: usize, incy: isize, c: f32, s: f32) -> Result<(), BlasError> {
+    rot_within::<f32>(n, buf, offx, incx, offy, incy, c, s).map_err(|e| report("srot_within", e))
+}
+
+/// `drot` on two non-overlapping vectors of the same buffer, see `rot_within`
+pub fn drot_within(n: isize, buf: &mut [f64], offx: usize, incx: isize, offy: usize, incy: isize, c: f64, s: f64) -> Result<(), BlasError> {
+    rot_within::<f64>(n, buf, offx, incx, offy, incy, c, s).map_err(|e| report("drot_within", e))
+}
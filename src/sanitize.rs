@@ -0,0 +1,283 @@
+use std::cell::Cell;
+use std::fmt;
+use std::sync::RwLock;
+use crate::trace::Arg;
+
+/// What the sanitizer found wrong with a call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FindingKind {
+    /// An input holds a NaN or an infinity.
+    NonFiniteInput,
+    /// An output holds a NaN or an infinity although every input was finite.
+    NonFiniteOutput,
+    /// The rotation `(c, s)` of `?rot` is not orthogonal: `c*c + s*s` is far from 1.
+    NotARotation,
+    /// An increment is zero.
+    ZeroIncrement,
+    /// A vector starts inside its slice but the stride walks past the end.
+    StrideOutOfRange,
+}
+
+/// Suspicious argument or result of a call, reported to the sanitize handler.
+///
+/// With the `sanitize` feature, every call of a typed BLAS routine (`saxpy`, `ddot`, ...) is audited, the same
+/// calls that verbose mode, recording and the statistics see. The variants on views, within one buffer, over
+/// batches, split into parts or on raw pointers (`*_view`, `*_within`, `*_batch`, `*_batch_strided`, `*_split`,
+/// `*_unchecked`) are not audited, nor are the routines generic over the element type (`axpy`, `dot`, ...).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Finding {
+    pub routine: &'static str,
+    pub kind: FindingKind,
+    /// Name of the offending argument or output.
+    pub arg: &'static str,
+    /// First offending element of a vector, counted along the vector from 0, `None` for a scalar.
+    pub index: Option<usize>,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let what = match self.kind {
+            FindingKind::NonFiniteInput => "holds a NaN or an infinity",
+            FindingKind::NonFiniteOutput => "came out as a NaN or an infinity from finite inputs",
+            FindingKind::NotARotation => "has c*c + s*s far from 1",
+            FindingKind::ZeroIncrement => "is a zero increment",
+            FindingKind::StrideOutOfRange => "walks past the end of its slice",
+        };
+        write!(f, "{}: {}", self.routine.to_uppercase(), self.arg)?;
+        if let Some(index) = self.index {
+            write!(f, "[{}]", index)?;
+        }
+        write!(f, " {}", what)
+    }
+}
+
+/// Sanitize handler, called with every finding of a call in the order the arguments come.
+pub type SanitizeHandler = fn(finding: &Finding);
+
+/// Prints the finding to standard error. This is the default handler.
+pub fn sanitize_log(finding: &Finding) {
+    eprintln!("RSBLAS_SANITIZE {}", finding);
+}
+
+/// Panics with the finding.
+pub fn sanitize_panic(finding: &Finding) {
+    panic!("RSBLAS_SANITIZE {}", finding);
+}
+
+static GLOBAL_HANDLER: RwLock<SanitizeHandler> = RwLock::new(sanitize_log);
+
+thread_local! {
+    static THREAD_HANDLER: Cell<Option<SanitizeHandler>> = const { Cell::new(None) };
+}
+
+/// Installs the process-wide sanitize handler and returns the previous one.
+pub fn set_sanitize_handler(handler: SanitizeHandler) -> SanitizeHandler {
+    let mut global = GLOBAL_HANDLER.write().unwrap_or_else(|e| e.into_inner());
+    std::mem::replace(&mut *global, handler)
+}
+
+/// Overrides the sanitize handler for the calling thread only, `None` falls back to the process-wide handler.
+/// Returns the previous override.
+pub fn set_thread_sanitize_handler(handler: Option<SanitizeHandler>) -> Option<SanitizeHandler> {
+    THREAD_HANDLER.with(|h| h.replace(handler))
+}
+
+fn emit(finding: Finding) {
+    let handler = THREAD_HANDLER.with(|h| h.get()).unwrap_or_else(|| {
+        *GLOBAL_HANDLER.read().unwrap_or_else(|e| e.into_inner())
+    });
+    handler(&finding);
+}
+
+/// vectors a routine only writes, not scanned as inputs
+const OUTPUT_ONLY: [(&str, &str); 2] = [("scopy", "y"), ("dcopy", "y")];
+
+/// audits the arguments of a call of `routine`, returns whether every input was finite
+pub(crate) fn check_inputs(routine: &'static str, args: &[(&'static str, Arg<'_>)]) -> bool {
+    let n = int_arg(args, "n").unwrap_or(0).max(0) as usize;
+    let mut finite = true;
+    for &(name, arg) in args {
+        let index = match arg {
+            Arg::Int(value) => {
+                if value == 0 && name.starts_with("inc") {
+                    emit(Finding { routine, kind: FindingKind::ZeroIncrement, arg: name, index: None });
+                }
+                continue;
+            }
+            Arg::F32(value) => if value.is_finite() { continue } else { None },
+            Arg::F64(value) => if value.is_finite() { continue } else { None },
+            Arg::F32s(x) => match vector(routine, name, n, args, x.len(), |i| x[i].is_finite()) {
+                Some(i) => Some(i),
+                None => continue,
+            },
+            Arg::F64s(x) => match vector(routine, name, n, args, x.len(), |i| x[i].is_finite()) {
+                Some(i) => Some(i),
+                None => continue,
+            },
+        };
+        finite = false;
+        emit(Finding { routine, kind: FindingKind::NonFiniteInput, arg: name, index });
+    }
+    if routine == "srot" || routine == "drot" {
+        check_rotation(routine, args);
+    }
+    finite
+}
+
+/// scans the outputs of a call whose inputs were all finite
+pub(crate) fn check_outputs(routine: &'static str, n: usize, inputs_finite: bool,
+                            outputs: &[(&'static str, Arg<'_>)], incs: &[(&'static str, isize)]) {
+    if !inputs_finite {
+        return;
+    }
+    for &(name, arg) in outputs {
+        let inc = incs.iter().find(|&&(v, _)| v == name).map_or(1, |&(_, inc)| inc);
+        let index = match arg {
+            Arg::Int(_) => continue,
+            Arg::F32(value) => if value.is_finite() { continue } else { None },
+            Arg::F64(value) => if value.is_finite() { continue } else { None },
+            Arg::F32s(x) => match first_bad(n, inc, x.len(), |i| x[i].is_finite()) {
+                Some(i) => Some(i),
+                None => continue,
+            },
+            Arg::F64s(x) => match first_bad(n, inc, x.len(), |i| x[i].is_finite()) {
+                Some(i) => Some(i),
+                None => continue,
+            },
+        };
+        emit(Finding { routine, kind: FindingKind::NonFiniteOutput, arg: name, index });
+    }
+}
+
+/// increment of every vector argument, to scan the outputs the same way as the inputs
+pub(crate) fn increments(args: &[(&'static str, Arg<'_>)]) -> Vec<(&'static str, isize)> {
+    args.iter()
+        .filter(|(_, arg)| matches!(arg, Arg::F32s(_) | Arg::F64s(_)))
+        .map(|&(name, _)| (name, inc_of(args, name)))
+        .collect()
+}
+
+/// checks the stride of vector `name` and scans it, the index of its first non-finite element if any
+fn vector(routine: &'static str, name: &'static str, n: usize, args: &[(&'static str, Arg<'_>)], len: usize,
+          is_finite: impl Fn(usize) -> bool) -> Option<usize> {
+    let inc = inc_of(args, name);
+    if inc == 0 || n == 0 {
+        return None;
+    }
+    if let Some(i) = (0 .. n).find(|&i| position(n, inc, i) >= len) {
+        if len > 0 {
+            emit(Finding { routine, kind: FindingKind::StrideOutOfRange, arg: name, index: Some(i) });
+        }
+    }
+    if OUTPUT_ONLY.contains(&(routine, name)) {
+        return None;
+    }
+    first_bad(n, inc, len, is_finite)
+}
+
+/// first of the `n` elements, within the slice, that is not finite
+fn first_bad(n: usize, inc: isize, len: usize, is_finite: impl Fn(usize) -> bool) -> Option<usize> {
+    if inc == 0 {
+        return None;
+    }
+    (0 .. n).find(|&i| {
+        let p = position(n, inc, i);
+        p < len && !is_finite(p)
+    })
+}
+
+/// slice position of element `i` of an `n`-element vector with increment `inc`
+fn position(n: usize, inc: isize, i: usize) -> usize {
+    let step = inc.unsigned_abs();
+    if inc > 0 { i * step } else { (n - 1 - i) * step }
+}
+
+fn inc_of(args: &[(&'static str, Arg<'_>)], vector: &str) -> isize {
+    args.iter()
+        .find_map(|&(name, arg)| match arg {
+            Arg::Int(value) if name.strip_prefix("inc") == Some(vector) => Some(value),
+            _ => None,
+        })
+        .unwrap_or(1)
+}
+
+fn int_arg(args: &[(&'static str, Arg<'_>)], name: &str) -> Option<isize> {
+    args.iter().find_map(|&(arg_name, arg)| match arg {
+        Arg::Int(value) if arg_name == name => Some(value),
+        _ => None,
+    })
+}
+
+fn check_rotation(routine: &'static str, args: &[(&'static str, Arg<'_>)]) {
+    let (mut c, mut s, mut eps) = (None, None, f64::EPSILON);
+    for &(name, arg) in args {
+        let value = match arg {
+            Arg::F32(value) => {
+                eps = f32::EPSILON as f64;
+                value as f64
+            }
+            Arg::F64(value) => value,
+            _ => continue,
+        };
+        match name {
+            "c" => c = Some(value),
+            "s" => s = Some(value),
+            _ => {}
+        }
+    }
+    if let (Some(c), Some(s)) = (c, s) {
+        if (c * c + s * s - 1.0).abs() > eps.sqrt() {
+            emit(Finding { routine, kind: FindingKind::NotARotation, arg: "c", index: None });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static FINDINGS: RefCell<Vec<Finding>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn capture(finding: &Finding) {
+        FINDINGS.with(|f| f.borrow_mut().push(*finding));
+    }
+
+    fn findings(f: impl FnOnce()) -> Vec<Finding> {
+        let prev = set_thread_sanitize_handler(Some(capture));
+        f();
+        set_thread_sanitize_handler(prev);
+        FINDINGS.with(|f| f.take())
+    }
+
+    #[test]
+    fn non_finite_values_are_reported_at_their_first_index() {
+        let found = findings(|| {
+            let mut y = [1.0, 2.0, 3.0];
+            crate::daxpy(3, 1.0, &[0.0, f64::NAN, f64::INFINITY], 1, &mut y, 1).unwrap();
+            let mut y = [1.0, 2.0];
+            crate::daxpy(2, f64::MAX, &[f64::MAX, 0.0], 1, &mut y, -1).unwrap();
+        });
+        assert_eq!(found, [
+            Finding { routine: "daxpy", kind: FindingKind::NonFiniteInput, arg: "x", index: Some(1) },
+            Finding { routine: "daxpy", kind: FindingKind::NonFiniteOutput, arg: "y", index: Some(0) },
+        ]);
+    }
+
+    #[test]
+    fn suspicious_arguments_are_flagged() {
+        let found = findings(|| {
+            let (mut x, mut y) = ([1.0_f32; 4], [1.0_f32; 4]);
+            crate::srot(2, &mut x, 1, &mut y, 1, 1.0, 1.0).unwrap();
+            let _ = crate::sdot(3, &x, 0, &y, 1);
+            let _ = crate::sscal(3, 2.0, &mut x, 2);
+        });
+        assert_eq!(found, [
+            Finding { routine: "srot", kind: FindingKind::NotARotation, arg: "c", index: None },
+            Finding { routine: "sdot", kind: FindingKind::ZeroIncrement, arg: "incx", index: None },
+            Finding { routine: "sscal", kind: FindingKind::StrideOutOfRange, arg: "x", index: Some(2) },
+        ]);
+    }
+}
@@ -1,53 +1,118 @@
-use std::ops::{MulAssign};
-
-trait ScalQuickReturn {
-    fn quick_return(a: Self) -> bool;
-}
-
-impl ScalQuickReturn for f32 {
-    fn quick_return(a: Self) -> bool {
-        a == 1.0_f32
-    }
-}
-impl ScalQuickReturn for f64 {
-    fn quick_return(a: Self) -> bool {
-        a == 1.0_f64
-    }
-}
-
-fn scal<T>(n: isize, a: T, x: &mut [T], incx: isize) -> bool
-where T: Default + MulAssign + Copy + ScalQuickReturn,
-{
-    if n <= 0 || incx <= 0 {
-        return false;
-    }
-
-    if x.len() < 1 + ((n as usize) - 1) * (incx as usize) {
-        return false;
-    }
-
-    if <T as ScalQuickReturn>::quick_return(a) {
-        return true;
-    }
-
-    let mut ix: usize = 0;
-
-    let n_usize = n as usize;
-    let incx_usize = incx as usize;
-    for _ in 0 .. n_usize {
-        x[ix] *= a;
-        ix += incx_usize;
-    }
-
-    true
-}
-
-/// Scales an `f32` vector by a constant.
-pub fn sscal(n: isize, a: f32, x: &mut [f32], incx: isize) -> bool {
-    scal::<f32>(n, a, x, incx)
-}
-
-/// Scales an `f64` vector by a constant.
-pub fn dscal(n: isize, a: f64, x: &mut [f64], incx: isize) -> bool {
-    scal::<f64>(n, a, x, incx)
-}
\ No newline at end of file
+use crate::error::BlasError;
+use crate::scalar::Scalar;
+use crate::xerbla::report;
+use crate::trace::{self, Arg};
+use crate::view::VecMut;
+use crate::utils::{check_n, check_inc_positive};
+use crate::strided::walk1;
+use crate::dispatch::{self, Kernels};
+use crate::parallel::{self, chunk};
+
+/// scales a vector by a constant, for any `Scalar` type
+pub fn scal<T>(n: isize, a: T, x: &mut [T], incx: isize) -> Result<(), BlasError>
+where T: Scalar,
+{
+    let n_usize = check_n(n, 1)?;
+    check_inc_positive(n_usize, x, incx, 3, "x", "incx")?;
+
+    // SAFETY: the slice was checked to hold `n` elements at increment `incx`.
+    unsafe { scal_unchecked(n_usize, a, x.as_mut_ptr(), incx) };
+    Ok(())
+}
+
+/// `scal` on a raw pointer, validated by debug assertions only, like CBLAS `cblas_?scal`
+///
+/// # Safety
+///
+/// `incx` must be positive and `x` valid for reads and writes of `(n - 1) * incx + 1` elements.
+pub unsafe fn scal_unchecked<T>(n: usize, a: T, x: *mut T, incx: isize)
+where T: Scalar,
+{
+    debug_assert!(n == 0 || !x.is_null());
+    debug_assert!(incx > 0, "incx must be positive");
+    if a == T::one() {
+        return;
+    }
+
+    let kernels = dispatch::kernels::<T>();
+    if kernels.is_some() {
+        // SAFETY: the chunks write disjoint parts of `x`, and kernels, hence `T`, are `f32` or `f64`.
+        let done = parallel::split(n, |lo, hi| scal_serial(kernels, hi - lo, a, chunk(x, n, incx, lo, hi), incx));
+        if done.is_some() {
+            return;
+        }
+    }
+    scal_serial(kernels, n, a, x, incx)
+}
+
+/// `scal_unchecked` on the calling thread
+unsafe fn scal_serial<T>(kernels: Option<Kernels<T>>, n: usize, a: T, x: *mut T, incx: isize)
+where T: Scalar,
+{
+    if let Some(kernels) = kernels {
+        if incx == 1 {
+            return (kernels.scal)(n, a, x);
+        }
+        if let Some(scal) = kernels.scal_strided {
+            return scal(n, a, x, incx);
+        }
+    }
+    walk1(n, x, incx, |px| *px *= a);
+}
+
+/// Scales an `f32` vector by a constant.
+pub fn sscal(n: isize, a: f32, x: &mut [f32], incx: isize) -> Result<(), BlasError> {
+    let call = trace::begin("sscal", &[
+        ("n", Arg::Int(n)), ("alpha", Arg::F32(a)), ("x", Arg::F32s(x)), ("incx", Arg::Int(incx)),
+    ]);
+    let result = scal::<f32>(n, a, x, incx).map_err(|e| report("sscal", e));
+    trace::end(call, result.is_ok(), &[("x", Arg::F32s(x))]);
+    result
+}
+
+/// Scales an `f64` vector by a constant.
+pub fn dscal(n: isize, a: f64, x: &mut [f64], incx: isize) -> Result<(), BlasError> {
+    let call = trace::begin("dscal", &[
+        ("n", Arg::Int(n)), ("alpha", Arg::F64(a)), ("x", Arg::F64s(x)), ("incx", Arg::Int(incx)),
+    ]);
+    let result = scal::<f64>(n, a, x, incx).map_err(|e| report("dscal", e));
+    trace::end(call, result.is_ok(), &[("x", Arg::F64s(x))]);
+    result
+}
+
+/// `sscal` on a raw pointer without bounds checks
+///
+/// # Safety
+///
+/// See `scal_unchecked`.
+pub unsafe fn sscal_unchecked(n: usize, a: f32, x: *mut f32, incx: isize) {
+    scal_unchecked::<f32>(n, a, x, incx)
+}
+
+/// `dscal` on a raw pointer without bounds checks
+///
+/// # Safety
+///
+/// See `scal_unchecked`.
+pub unsafe fn dscal_unchecked(n: usize, a: f64, x: *mut f64, incx: isize) {
+    scal_unchecked::<f64>(n, a, x, incx)
+}
+
+/// scales a vector view by a constant, for any `Scalar` type
+pub fn scal_view<T>(a: T, x: &mut VecMut<'_, T>)
+where T: Scalar,
+{
+    let mut x = x.forward();
+    let (n, x, incx) = x.as_blas();
+    scal(n, a, x, incx).unwrap_or_else(|_| unreachable!("views are validated on construction"))
+}
+
+/// `sscal` on a vector view
+pub fn sscal_view(a: f32, x: &mut VecMut<'_, f32>) {
+    scal_view::<f32>(a, x)
+}
+
+/// `dscal` on a vector view
+pub fn dscal_view(a: f64, x: &mut VecMut<'_, f64>) {
+    scal_view::<f64>(a, x)
+}
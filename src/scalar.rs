@@ -0,0 +1,130 @@
+use std::fmt::Debug;
+use std::ops::{Add, AddAssign, Sub, SubAssign, Mul, MulAssign, Div, Neg};
+use crate::Complex;
+
+/// Element type accepted by the generic kernels (`axpy::<T>`, `dot::<T>`, ...).
+///
+/// The trait is open: implement it, together with `RealScalar` for the matching real type, to run your own
+/// number types through the same kernels as `f32` and `f64`.
+pub trait Scalar:
+    'static + Copy + Default + Debug + PartialEq
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Neg<Output = Self>
+    + AddAssign + SubAssign + MulAssign
+{
+    /// Real type of the magnitude and of each component.
+    type Real: RealScalar;
+
+    fn zero() -> Self;
+    fn one() -> Self;
+
+    /// Lifts a real value into `Self`, with a zero imaginary part.
+    fn from_real(re: Self::Real) -> Self;
+    /// Real part.
+    fn re(self) -> Self::Real;
+    /// Imaginary part, zero for real types.
+    fn im(self) -> Self::Real;
+    /// Complex conjugate, the identity for real types.
+    fn conj(self) -> Self;
+    /// Magnitude `|x|`.
+    fn abs(self) -> Self::Real;
+    /// `|re(x)| + |im(x)|`, the magnitude used by `asum`.
+    fn abs1(self) -> Self::Real {
+        self.re().abs() + self.im().abs()
+    }
+    /// Principal square root.
+    fn sqrt(self) -> Self;
+}
+
+/// Real, ordered `Scalar`, with the machine constants needed by scaling-aware kernels such as `rotg`.
+pub trait RealScalar: Scalar<Real = Self> + PartialOrd {
+    /// Difference between `1` and the next representable value.
+    fn epsilon() -> Self;
+    /// Smallest positive value whose reciprocal does not overflow, LAPACK `?LAMCH('S')`.
+    fn safe_min() -> Self;
+    /// Largest finite value.
+    fn max_value() -> Self;
+    /// `sqrt(self * self + other * other)` without undue overflow or underflow.
+    fn hypot(self, other: Self) -> Self;
+    /// Converts an `f64` constant, rounding if needed.
+    fn from_f64(x: f64) -> Self;
+    /// `e^self`.
+    fn exp(self) -> Self;
+    /// Natural logarithm.
+    fn ln(self) -> Self;
+    /// `self^p`.
+    fn powf(self, p: Self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    /// Four-quadrant arctangent of `self / other`, in `[-pi, pi]`.
+    fn atan2(self, other: Self) -> Self;
+    /// Whether the sign bit is set, true for `-0.0` as well.
+    fn is_sign_negative(self) -> bool;
+}
+
+/// Complex `Scalar` built from two `Real` components.
+pub trait ComplexScalar: Scalar {
+    fn new(re: Self::Real, im: Self::Real) -> Self;
+}
+
+macro_rules! impl_real_scalar {
+    ($t:ident) => (
+        impl Scalar for $t {
+            type Real = $t;
+
+            fn zero() -> Self { 0.0 }
+            fn one() -> Self { 1.0 }
+
+            fn from_real(re: Self) -> Self { re }
+            fn re(self) -> Self { self }
+            fn im(self) -> Self { 0.0 }
+            fn conj(self) -> Self { self }
+            fn abs(self) -> Self { $t::abs(self) }
+            fn abs1(self) -> Self { $t::abs(self) }
+            fn sqrt(self) -> Self { $t::sqrt(self) }
+        }
+
+        impl RealScalar for $t {
+            fn epsilon() -> Self { $t::EPSILON }
+            fn safe_min() -> Self { $t::MIN_POSITIVE }
+            fn max_value() -> Self { $t::MAX }
+            fn hypot(self, other: Self) -> Self { $t::hypot(self, other) }
+            fn from_f64(x: f64) -> Self { x as $t }
+            fn exp(self) -> Self { $t::exp(self) }
+            fn ln(self) -> Self { $t::ln(self) }
+            fn powf(self, p: Self) -> Self { $t::powf(self, p) }
+            fn sin(self) -> Self { $t::sin(self) }
+            fn cos(self) -> Self { $t::cos(self) }
+            fn atan2(self, other: Self) -> Self { $t::atan2(self, other) }
+            fn is_sign_negative(self) -> bool { $t::is_sign_negative(self) }
+        }
+    )
+}
+impl_real_scalar!(f32);
+impl_real_scalar!(f64);
+
+impl<T: RealScalar> Scalar for Complex<T> {
+    type Real = T;
+
+    fn zero() -> Self { Complex::new(T::zero(), T::zero()) }
+    fn one() -> Self { Complex::new(T::one(), T::zero()) }
+
+    fn from_real(re: T) -> Self { Complex::new(re, T::zero()) }
+    fn re(self) -> T { self.re }
+    fn im(self) -> T { self.im }
+    fn conj(self) -> Self { Complex::new(self.re, -self.im) }
+    fn abs(self) -> T { Complex::abs(self) }
+    fn sqrt(self) -> Self {
+        let r = Complex::abs(self);
+        if r == T::zero() {
+            return Self::zero();
+        }
+        let half = T::from_f64(0.5);
+        let re = T::sqrt((r + self.re) * half);
+        let im = T::sqrt((r - self.re) * half);
+        Complex::new(re, if self.im < T::zero() { -im } else { im })
+    }
+}
+
+impl<T: RealScalar> ComplexScalar for Complex<T> {
+    fn new(re: T, im: T) -> Self { Complex::new(re, im) }
+}
@@ -0,0 +1,223 @@
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+use crate::scalar::{Scalar, RealScalar};
+
+/// Vector of `LANES` elements of a real type on one instruction set, the building block of the kernels in
+/// `kernels.rs`.
+///
+/// Every operation is `#[inline(always)]` so that it compiles to the backend's instructions inside a kernel
+/// entry point carrying the matching `#[target_feature]`; calling one from anywhere else needs the CPU support
+/// anyway, hence `unsafe`.
+pub(crate) trait Simd {
+    type Elem: RealScalar;
+    type Vector: Copy;
+    const LANES: usize;
+
+    unsafe fn zero() -> Self::Vector;
+    unsafe fn splat(x: Self::Elem) -> Self::Vector;
+    /// loads `LANES` consecutive elements from `p`, which need not be aligned
+    unsafe fn load(p: *const Self::Elem) -> Self::Vector;
+    /// stores `LANES` consecutive elements to `p`, which need not be aligned
+    unsafe fn store(p: *mut Self::Elem, v: Self::Vector);
+    unsafe fn add(a: Self::Vector, b: Self::Vector) -> Self::Vector;
+    unsafe fn sub(a: Self::Vector, b: Self::Vector) -> Self::Vector;
+    unsafe fn mul(a: Self::Vector, b: Self::Vector) -> Self::Vector;
+    unsafe fn abs(a: Self::Vector) -> Self::Vector;
+
+    /// `a * b + c`, fused where the instruction set has it
+    #[inline(always)]
+    unsafe fn fma(a: Self::Vector, b: Self::Vector, c: Self::Vector) -> Self::Vector {
+        Self::add(Self::mul(a, b), c)
+    }
+
+    /// `a * b - c`, fused where the instruction set has it
+    #[inline(always)]
+    unsafe fn fms(a: Self::Vector, b: Self::Vector, c: Self::Vector) -> Self::Vector {
+        Self::sub(Self::mul(a, b), c)
+    }
+
+    /// sum of the lanes, added in lane order
+    #[inline(always)]
+    unsafe fn hsum(v: Self::Vector) -> Self::Elem {
+        let mut lanes = [Self::Elem::zero(); 8];
+        Self::store(lanes.as_mut_ptr(), v);
+        let mut sum = lanes[0];
+        for &x in &lanes[1 .. Self::LANES] {
+            sum += x;
+        }
+        sum
+    }
+
+    /// loads the elements at `p`, `p + inc`, ..., `p + (LANES - 1) * inc`
+    #[inline(always)]
+    unsafe fn gather(p: *const Self::Elem, inc: isize) -> Self::Vector {
+        gather_lanes::<Self>(p, inc)
+    }
+
+    /// stores the lanes to `p`, `p + inc`, ..., `p + (LANES - 1) * inc`
+    #[inline(always)]
+    unsafe fn scatter(p: *mut Self::Elem, inc: isize, v: Self::Vector) {
+        let mut lanes = [Self::Elem::zero(); 8];
+        Self::store(lanes.as_mut_ptr(), v);
+        for (l, &lane) in lanes[.. Self::LANES].iter().enumerate() {
+            *p.offset(l as isize * inc) = lane;
+        }
+    }
+}
+
+/// `Simd::gather` one lane at a time
+#[inline(always)]
+unsafe fn gather_lanes<S: Simd + ?Sized>(p: *const S::Elem, inc: isize) -> S::Vector {
+    let mut lanes = [S::Elem::zero(); 8];
+    for (l, lane) in lanes[.. S::LANES].iter_mut().enumerate() {
+        *lane = *p.offset(l as isize * inc);
+    }
+    S::load(lanes.as_ptr())
+}
+
+/// one-lane backend in plain Rust, for targets without vector instructions and for testing the kernels
+pub(crate) struct Portable<T>(PhantomData<T>);
+
+impl<T: RealScalar> Simd for Portable<T> {
+    type Elem = T;
+    type Vector = T;
+    const LANES: usize = 1;
+
+    #[inline(always)]
+    unsafe fn zero() -> T { T::zero() }
+    #[inline(always)]
+    unsafe fn splat(x: T) -> T { x }
+    #[inline(always)]
+    unsafe fn load(p: *const T) -> T { *p }
+    #[inline(always)]
+    unsafe fn store(p: *mut T, v: T) { *p = v }
+    #[inline(always)]
+    unsafe fn add(a: T, b: T) -> T { a + b }
+    #[inline(always)]
+    unsafe fn sub(a: T, b: T) -> T { a - b }
+    #[inline(always)]
+    unsafe fn mul(a: T, b: T) -> T { a * b }
+    #[inline(always)]
+    unsafe fn abs(a: T) -> T { a.abs() }
+    #[inline(always)]
+    unsafe fn hsum(v: T) -> T { v }
+}
+
+/// 128-bit SSE2 backend, without fused multiply-add
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub(crate) struct Sse2<T>(PhantomData<T>);
+
+/// 256-bit AVX2 backend with fused multiply-add, needs both `avx2` and `fma`
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub(crate) struct Avx2<T>(PhantomData<T>);
+
+macro_rules! x86_backend {
+    ($backend:ident, $t:ident, $vector:ty, $lanes:expr, $setzero:ident, $splat:expr, $loadu:ident, $storeu:ident,
+     $add:ident, $sub:ident, $mul:ident, $andnot:ident $(, $fmadd:ident, $fmsub:ident, $gather:ident)?) => {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        impl Simd for $backend<$t> {
+            type Elem = $t;
+            type Vector = $vector;
+            const LANES: usize = $lanes;
+
+            #[inline(always)]
+            unsafe fn zero() -> $vector { $setzero() }
+            #[inline(always)]
+            unsafe fn splat(x: $t) -> $vector { $splat(x) }
+            #[inline(always)]
+            unsafe fn load(p: *const $t) -> $vector { $loadu(p) }
+            #[inline(always)]
+            unsafe fn store(p: *mut $t, v: $vector) { $storeu(p, v) }
+            #[inline(always)]
+            unsafe fn add(a: $vector, b: $vector) -> $vector { $add(a, b) }
+            #[inline(always)]
+            unsafe fn sub(a: $vector, b: $vector) -> $vector { $sub(a, b) }
+            #[inline(always)]
+            unsafe fn mul(a: $vector, b: $vector) -> $vector { $mul(a, b) }
+            #[inline(always)]
+            unsafe fn abs(a: $vector) -> $vector { $andnot($splat(-0.0), a) }
+            $(
+                #[inline(always)]
+                unsafe fn fma(a: $vector, b: $vector, c: $vector) -> $vector { $fmadd(a, b, c) }
+                #[inline(always)]
+                unsafe fn fms(a: $vector, b: $vector, c: $vector) -> $vector { $fmsub(a, b, c) }
+                #[inline(always)]
+                unsafe fn gather(p: *const $t, inc: isize) -> $vector {
+                    match $gather(p, inc) {
+                        Some(v) => v,
+                        None => gather_lanes::<Self>(p, inc),
+                    }
+                }
+            )?
+        }
+    };
+}
+
+x86_backend!(Sse2, f32, __m128, 4, _mm_setzero_ps, _mm_set1_ps, _mm_loadu_ps, _mm_storeu_ps, _mm_add_ps,
+             _mm_sub_ps, _mm_mul_ps, _mm_andnot_ps);
+x86_backend!(Sse2, f64, __m128d, 2, _mm_setzero_pd, _mm_set1_pd, _mm_loadu_pd, _mm_storeu_pd, _mm_add_pd,
+             _mm_sub_pd, _mm_mul_pd, _mm_andnot_pd);
+x86_backend!(Avx2, f32, __m256, 8, _mm256_setzero_ps, _mm256_set1_ps, _mm256_loadu_ps, _mm256_storeu_ps,
+             _mm256_add_ps, _mm256_sub_ps, _mm256_mul_ps, _mm256_andnot_ps, _mm256_fmadd_ps, _mm256_fmsub_ps,
+             avx2_gather_ps);
+x86_backend!(Avx2, f64, __m256d, 4, _mm256_setzero_pd, _mm256_set1_pd, _mm256_loadu_pd, _mm256_storeu_pd,
+             _mm256_add_pd, _mm256_sub_pd, _mm256_mul_pd, _mm256_andnot_pd, _mm256_fmadd_pd, _mm256_fmsub_pd,
+             avx2_gather_pd);
+
+/// `vgatherdps` of 8 elements `inc` apart, `None` when the offsets do not fit the 32-bit lane indices
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline(always)]
+unsafe fn avx2_gather_ps(p: *const f32, inc: isize) -> Option<__m256> {
+    let inc = i32::try_from(inc).ok().filter(|inc| inc.checked_mul(7).is_some())?;
+    let offsets = _mm256_setr_epi32(0, inc, 2 * inc, 3 * inc, 4 * inc, 5 * inc, 6 * inc, 7 * inc);
+    Some(_mm256_i32gather_ps(p, offsets, 4))
+}
+
+/// `vgatherdpd` of 4 elements `inc` apart, `None` when the offsets do not fit the 32-bit lane indices
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline(always)]
+unsafe fn avx2_gather_pd(p: *const f64, inc: isize) -> Option<__m256d> {
+    let inc = i32::try_from(inc).ok().filter(|inc| inc.checked_mul(3).is_some())?;
+    Some(_mm256_i32gather_pd(p, _mm_setr_epi32(0, inc, 2 * inc, 3 * inc), 8))
+}
+
+/// copies `len` bytes from `src` to `dst`, 32 at a time
+///
+/// # Safety
+///
+/// The CPU must support AVX2, `src` be valid for reads and `dst` for writes of `len` bytes, and the two ranges
+/// must not overlap.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn copy_bytes(len: usize, src: *const u8, dst: *mut u8) {
+    let mut i = 0;
+    while i + 32 <= len {
+        _mm256_storeu_si256(dst.add(i) as *mut __m256i, _mm256_loadu_si256(src.add(i) as *const __m256i));
+        i += 32;
+    }
+    std::ptr::copy_nonoverlapping(src.add(i), dst.add(i), len - i);
+}
+
+/// exchanges `len` bytes between `x` and `y`, 32 at a time
+///
+/// # Safety
+///
+/// The CPU must support AVX2, `x` and `y` be valid for reads and writes of `len` bytes, and the two ranges must
+/// not overlap.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn swap_bytes(len: usize, x: *mut u8, y: *mut u8) {
+    let mut i = 0;
+    while i + 32 <= len {
+        let (px, py) = (x.add(i) as *mut __m256i, y.add(i) as *mut __m256i);
+        let (vx, vy) = (_mm256_loadu_si256(px), _mm256_loadu_si256(py));
+        _mm256_storeu_si256(px, vy);
+        _mm256_storeu_si256(py, vx);
+        i += 32;
+    }
+    std::ptr::swap_nonoverlapping(x.add(i), y.add(i), len - i);
+}
@@ -0,0 +1,253 @@
+use crate::asum::asum;
+use crate::axpy::axpy;
+use crate::complex::Complex;
+use crate::dot::dot;
+use crate::error::BlasError;
+use crate::nrm2::nrm2;
+use crate::rot::rot;
+use crate::scalar::RealScalar;
+use crate::view::check_len;
+use crate::xerbla::report;
+
+/// Complex vector stored as separate real and imaginary arrays ("split" or planar storage), element `i` being
+/// `re[i] + i im[i]`.
+///
+/// The complex routines on it (`axpy_split`, `dotu_split`, ...) are sequences of real kernels over whole
+/// arrays, so they run at the speed of `saxpy`/`sdot` without deinterleaving the data.
+#[derive(Debug, Clone, Copy)]
+pub struct SplitComplex<'a, T> {
+    re: &'a [T],
+    im: &'a [T],
+}
+
+/// Mutable counterpart of `SplitComplex`.
+#[derive(Debug)]
+pub struct SplitComplexMut<'a, T> {
+    re: &'a mut [T],
+    im: &'a mut [T],
+}
+
+impl<'a, T: RealScalar> SplitComplex<'a, T> {
+    /// Fails if the two arrays differ in length.
+    pub fn new(re: &'a [T], im: &'a [T]) -> Result<Self, BlasError> {
+        check_len(re.len(), im.len(), 2, "im")?;
+        Ok(SplitComplex { re, im })
+    }
+
+    pub fn len(&self) -> usize {
+        self.re.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.re.is_empty()
+    }
+
+    pub fn re(&self) -> &'a [T] {
+        self.re
+    }
+
+    pub fn im(&self) -> &'a [T] {
+        self.im
+    }
+
+    pub fn get(&self, i: usize) -> Option<Complex<T>> {
+        Some(Complex::new(*self.re.get(i)?, self.im[i]))
+    }
+}
+
+impl<'a, T: RealScalar> SplitComplexMut<'a, T> {
+    /// Fails if the two arrays differ in length.
+    pub fn new(re: &'a mut [T], im: &'a mut [T]) -> Result<Self, BlasError> {
+        check_len(re.len(), im.len(), 2, "im")?;
+        Ok(SplitComplexMut { re, im })
+    }
+
+    pub fn len(&self) -> usize {
+        self.re.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.re.is_empty()
+    }
+
+    pub fn re(&self) -> &[T] {
+        self.re
+    }
+
+    pub fn im(&self) -> &[T] {
+        self.im
+    }
+
+    pub fn get(&self, i: usize) -> Option<Complex<T>> {
+        Some(Complex::new(*self.re.get(i)?, self.im[i]))
+    }
+
+    /// Overwrites element `i`, panics if `i` is out of range.
+    pub fn set(&mut self, i: usize, z: Complex<T>) {
+        self.re[i] = z.re;
+        self.im[i] = z.im;
+    }
+
+    pub fn as_ref(&self) -> SplitComplex<'_, T> {
+        SplitComplex { re: self.re, im: self.im }
+    }
+
+    /// Shorter-lived mutable view of the same arrays.
+    pub fn rb_mut(&mut self) -> SplitComplexMut<'_, T> {
+        SplitComplexMut { re: self.re, im: self.im }
+    }
+}
+
+/// adds a complex multiple of a split complex vector to another, `y := a * x + y`, as four real `axpy`
+pub fn axpy_split<T>(a: Complex<T>, x: SplitComplex<'_, T>, y: &mut SplitComplexMut<'_, T>) -> Result<(), BlasError>
+where T: RealScalar,
+{
+    check_len(x.len(), y.len(), 3, "y")?;
+    let n = x.len() as isize;
+    axpy(n, a.re, x.re, 1, y.re, 1)?;
+    axpy(n, -a.im, x.im, 1, y.re, 1)?;
+    axpy(n, a.re, x.im, 1, y.im, 1)?;
+    axpy(n, a.im, x.re, 1, y.im, 1)
+}
+
+/// `caxpy` on split complex `f32` vectors
+pub fn caxpy_split(a: Complex<f32>, x: SplitComplex<'_, f32>, y: &mut SplitComplexMut<'_, f32>) -> Result<(), BlasError> {
+    axpy_split::<f32>(a, x, y).map_err(|e| report("caxpy_split", e))
+}
+
+/// `zaxpy` on split complex `f64` vectors
+pub fn zaxpy_split(a: Complex<f64>, x: SplitComplex<'_, f64>, y: &mut SplitComplexMut<'_, f64>) -> Result<(), BlasError> {
+    axpy_split::<f64>(a, x, y).map_err(|e| report("zaxpy_split", e))
+}
+
+/// unconjugated dot product `sum(x[i] * y[i])` of split complex vectors, as four real `dot`
+pub fn dotu_split<T>(x: SplitComplex<'_, T>, y: SplitComplex<'_, T>) -> Result<Complex<T>, BlasError>
+where T: RealScalar,
+{
+    check_len(x.len(), y.len(), 2, "y")?;
+    let n = x.len() as isize;
+    let re = dot(n, x.re, 1, y.re, 1)? - dot(n, x.im, 1, y.im, 1)?;
+    let im = dot(n, x.re, 1, y.im, 1)? + dot(n, x.im, 1, y.re, 1)?;
+    Ok(Complex::new(re, im))
+}
+
+/// conjugated dot product `sum(conj(x[i]) * y[i])` of split complex vectors, as four real `dot`
+pub fn dotc_split<T>(x: SplitComplex<'_, T>, y: SplitComplex<'_, T>) -> Result<Complex<T>, BlasError>
+where T: RealScalar,
+{
+    check_len(x.len(), y.len(), 2, "y")?;
+    let n = x.len() as isize;
+    let re = dot(n, x.re, 1, y.re, 1)? + dot(n, x.im, 1, y.im, 1)?;
+    let im = dot(n, x.re, 1, y.im, 1)? - dot(n, x.im, 1, y.re, 1)?;
+    Ok(Complex::new(re, im))
+}
+
+/// `cdotu` on split complex `f32` vectors
+pub fn cdotu_split(x: SplitComplex<'_, f32>, y: SplitComplex<'_, f32>) -> Result<Complex<f32>, BlasError> {
+    dotu_split::<f32>(x, y).map_err(|e| report("cdotu_split", e))
+}
+
+/// `zdotu` on split complex `f64` vectors
+pub fn zdotu_split(x: SplitComplex<'_, f64>, y: SplitComplex<'_, f64>) -> Result<Complex<f64>, BlasError> {
+    dotu_split::<f64>(x, y).map_err(|e| report("zdotu_split", e))
+}
+
+/// `cdotc` on split complex `f32` vectors
+pub fn cdotc_split(x: SplitComplex<'_, f32>, y: SplitComplex<'_, f32>) -> Result<Complex<f32>, BlasError> {
+    dotc_split::<f32>(x, y).map_err(|e| report("cdotc_split", e))
+}
+
+/// `zdotc` on split complex `f64` vectors
+pub fn zdotc_split(x: SplitComplex<'_, f64>, y: SplitComplex<'_, f64>) -> Result<Complex<f64>, BlasError> {
+    dotc_split::<f64>(x, y).map_err(|e| report("zdotc_split", e))
+}
+
+/// scales a split complex vector by a complex constant
+///
+/// `(re + i im) * (ar + i ai)` is the plane rotation of `(re, im)` by `c = ar`, `s = -ai`, so this is a single
+/// real `rot` over the two arrays.
+pub fn scal_split<T>(a: Complex<T>, x: &mut SplitComplexMut<'_, T>)
+where T: RealScalar,
+{
+    let n = x.len() as isize;
+    rot(n, x.re, 1, x.im, 1, a.re, -a.im).unwrap_or_else(|_| unreachable!("split vectors are validated on construction"))
+}
+
+/// `cscal` on a split complex `f32` vector
+pub fn cscal_split(a: Complex<f32>, x: &mut SplitComplexMut<'_, f32>) {
+    scal_split::<f32>(a, x)
+}
+
+/// `zscal` on a split complex `f64` vector
+pub fn zscal_split(a: Complex<f64>, x: &mut SplitComplexMut<'_, f64>) {
+    scal_split::<f64>(a, x)
+}
+
+/// sums `|re(x[i])| + |im(x[i])|` of a split complex vector, as two real `asum`
+pub fn asum_split<T>(x: SplitComplex<'_, T>) -> T
+where T: RealScalar,
+{
+    let n = x.len() as isize;
+    let sum = asum(n, x.re, 1).and_then(|re| Ok(re + asum(n, x.im, 1)?));
+    sum.unwrap_or_else(|_| unreachable!("split vectors are validated on construction"))
+}
+
+/// `scasum` on a split complex `f32` vector
+pub fn scasum_split(x: SplitComplex<'_, f32>) -> f32 {
+    asum_split::<f32>(x)
+}
+
+/// `dzasum` on a split complex `f64` vector
+pub fn dzasum_split(x: SplitComplex<'_, f64>) -> f64 {
+    asum_split::<f64>(x)
+}
+
+/// Euclidean norm of a split complex vector, the `hypot` of the norms of the two real arrays
+pub fn nrm2_split<T>(x: SplitComplex<'_, T>) -> T
+where T: RealScalar,
+{
+    let n = x.len() as isize;
+    let norm = nrm2(n, x.re, 1).and_then(|re| Ok(re.hypot(nrm2(n, x.im, 1)?)));
+    norm.unwrap_or_else(|_| unreachable!("split vectors are validated on construction"))
+}
+
+/// `scnrm2` on a split complex `f32` vector
+pub fn scnrm2_split(x: SplitComplex<'_, f32>) -> f32 {
+    nrm2_split::<f32>(x)
+}
+
+/// `dznrm2` on a split complex `f64` vector
+pub fn dznrm2_split(x: SplitComplex<'_, f64>) -> f64 {
+    nrm2_split::<f64>(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Complex64;
+
+    #[test]
+    fn split_routines_match_interleaved_arithmetic() {
+        let (xr, xi) = ([1.0_f64, 2.0, 0.0], [0.0_f64, -1.0, 3.0]);
+        let (mut yr, mut yi) = ([1.0_f64, 1.0, 1.0], [1.0_f64, 0.0, -2.0]);
+        let x = SplitComplex::new(&xr, &xi).unwrap();
+        let a = Complex64::new(2.0, 1.0);
+        let mut y = SplitComplexMut::new(&mut yr, &mut yi).unwrap();
+        let expected: Vec<_> = (0 .. 3).map(|i| a * x.get(i).unwrap() + y.get(i).unwrap()).collect();
+        zaxpy_split(a, x, &mut y).unwrap();
+        assert_eq!((0 .. 3).map(|i| y.get(i).unwrap()).collect::<Vec<_>>(), expected);
+
+        let dotu = (0 .. 3).fold(Complex64::default(), |s, i| s + x.get(i).unwrap() * y.get(i).unwrap());
+        let dotc = (0 .. 3).fold(Complex64::default(), |s, i| s + x.get(i).unwrap().conj() * y.get(i).unwrap());
+        assert_eq!(zdotu_split(x, y.as_ref()), Ok(dotu));
+        assert_eq!(zdotc_split(x, y.as_ref()), Ok(dotc));
+
+        zscal_split(a, &mut y);
+        assert_eq!(y.get(2), Some(expected[2] * a));
+        assert_eq!(dzasum_split(x), 7.0);
+        assert_eq!(dznrm2_split(x), 15.0_f64.sqrt());
+
+        assert_eq!(SplitComplex::new(&xr, &xi[.. 2]).unwrap_err(),
+                   BlasError::DimensionMismatch { pos: 2, name: "im", expected: 3, actual: 2 });
+    }
+}
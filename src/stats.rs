@@ -0,0 +1,166 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// Routines counted by the statistics, the typed BLAS entry points.
+const ROUTINES: [&str; 18] = [
+    "sasum", "dasum", "saxpy", "daxpy", "scopy", "dcopy", "sdot", "ddot", "snrm2", "dnrm2",
+    "srot", "drot", "srotg", "drotg", "sscal", "dscal", "sswap", "dswap",
+];
+
+struct Counters {
+    calls: AtomicU64,
+    flops: AtomicU64,
+    bytes: AtomicU64,
+}
+
+impl Counters {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const ZERO: Counters = Counters { calls: AtomicU64::new(0), flops: AtomicU64::new(0), bytes: AtomicU64::new(0) };
+}
+
+/// Call, floating-point operation and memory traffic counts per routine.
+///
+/// Counting is off until `set_stats(true)`; from then on every valid call of a typed BLAS routine adds to the
+/// process-wide `global_stats()` and to the `stats()` of the `Context` it runs under. Flops and bytes are the
+/// nominal counts of the routine on `n` elements, e.g. `2n` flops and `3n` words for `?axpy`, whatever the
+/// increments.
+pub struct Stats {
+    counters: [Counters; ROUTINES.len()],
+}
+
+/// Counts of one routine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoutineStats {
+    pub routine: &'static str,
+    pub calls: u64,
+    pub flops: u64,
+    pub bytes: u64,
+}
+
+/// Counts of every routine called at least once, taken by `Stats::snapshot`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatsSnapshot {
+    pub routines: Vec<RoutineStats>,
+}
+
+impl StatsSnapshot {
+    pub fn get(&self, routine: &str) -> Option<&RoutineStats> {
+        self.routines.iter().find(|r| r.routine == routine)
+    }
+
+    /// Sum over all routines, with the routine name `"total"`.
+    pub fn total(&self) -> RoutineStats {
+        self.routines.iter().fold(RoutineStats { routine: "total", calls: 0, flops: 0, bytes: 0 }, |t, r| {
+            RoutineStats { calls: t.calls + r.calls, flops: t.flops + r.flops, bytes: t.bytes + r.bytes, ..t }
+        })
+    }
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Stats::new()
+    }
+}
+
+impl std::fmt::Debug for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.snapshot().fmt(f)
+    }
+}
+
+impl Stats {
+    pub const fn new() -> Self {
+        Stats { counters: [Counters::ZERO; ROUTINES.len()] }
+    }
+
+    pub fn snapshot(&self) -> StatsSnapshot {
+        let routines = ROUTINES.iter().zip(&self.counters)
+            .map(|(&routine, c)| RoutineStats {
+                routine,
+                calls: c.calls.load(Ordering::Relaxed),
+                flops: c.flops.load(Ordering::Relaxed),
+                bytes: c.bytes.load(Ordering::Relaxed),
+            })
+            .filter(|r| r.calls > 0)
+            .collect();
+        StatsSnapshot { routines }
+    }
+
+    /// Sets every count back to zero.
+    pub fn reset(&self) {
+        for c in &self.counters {
+            c.calls.store(0, Ordering::Relaxed);
+            c.flops.store(0, Ordering::Relaxed);
+            c.bytes.store(0, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn add(&self, routine: &str, flops: u64, bytes: u64) {
+        if let Some(c) = ROUTINES.iter().position(|&r| r == routine).map(|i| &self.counters[i]) {
+            c.calls.fetch_add(1, Ordering::Relaxed);
+            c.flops.fetch_add(flops, Ordering::Relaxed);
+            c.bytes.fetch_add(bytes, Ordering::Relaxed);
+        }
+    }
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static GLOBAL_STATS: Stats = Stats::new();
+
+/// Turns counting on or off and returns the previous setting.
+pub fn set_stats(on: bool) -> bool {
+    ENABLED.swap(on, Ordering::Relaxed)
+}
+
+pub fn stats_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Counts of every call made in the process while counting was on.
+pub fn global_stats() -> &'static Stats {
+    &GLOBAL_STATS
+}
+
+/// words read or written by a Level 1 routine, named without its type prefix, on `n` elements
+pub(crate) fn words(routine: &str, n: usize) -> u64 {
+    let per_element = match routine {
+        "asum" | "nrm2" => 1,
+        "copy" | "dot" | "scal" => 2,
+        "axpy" => 3,
+        "rot" | "swap" => 4,
+        _ => 0,
+    };
+    per_element * n as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Context;
+
+    #[test]
+    fn calls_are_counted_per_context() {
+        let ctx = Context::new();
+        let prev = set_stats(true);
+        ctx.install(|| {
+            let mut y = [0.0_f32; 4];
+            crate::saxpy(4, 2.0, &[1.0; 4], 1, &mut y, 1).unwrap();
+            crate::saxpy(2, 2.0, &[1.0; 4], 2, &mut y, 1).unwrap();
+            crate::ddot(3, &[1.0; 3], 1, &[1.0; 3], 1).unwrap();
+            // calls rejected by validation are not counted
+            let prev_xerbla = crate::set_thread_xerbla(Some(crate::xerbla_silent));
+            assert!(crate::saxpy(8, 2.0, &[1.0; 4], 1, &mut y, 1).is_err());
+            assert!(crate::ddot(3, &[1.0; 3], 0, &[1.0; 3], 1).is_err());
+            crate::set_thread_xerbla(prev_xerbla);
+        });
+        set_stats(prev);
+
+        let snapshot = ctx.stats().snapshot();
+        assert_eq!(snapshot.get("saxpy"), Some(&RoutineStats { routine: "saxpy", calls: 2, flops: 12, bytes: 72 }));
+        assert_eq!(snapshot.get("ddot"), Some(&RoutineStats { routine: "ddot", calls: 1, flops: 6, bytes: 48 }));
+        assert_eq!(snapshot.get("sscal"), None);
+        assert_eq!(snapshot.total().calls, 3);
+        assert!(global_stats().snapshot().get("saxpy").map_or(0, |s| s.calls) >= 2);
+        ctx.stats().reset();
+        assert_eq!(ctx.stats().snapshot(), StatsSnapshot::default());
+    }
+}
@@ -0,0 +1,103 @@
+use crate::utils::get_first_index;
+
+/// pointer to element 0 of the `n`-element vector stored at `p` with increment `inc`, `n > 0`
+#[inline(always)]
+unsafe fn first<T>(p: *mut T, n: usize, inc: isize) -> *mut T {
+    if inc > 0 { p } else { p.add(get_first_index(n, inc)) }
+}
+
+/// calls `f` with each element of the vector `x`, an indexed loop for a unit increment and a pointer walk from
+/// element 0 otherwise
+///
+/// # Safety
+///
+/// `x` must be valid for `(n - 1) * |incx| + 1` elements, for whatever accesses `f` makes.
+#[inline(always)]
+pub(crate) unsafe fn walk1<X>(n: usize, x: *mut X, incx: isize, mut f: impl FnMut(*mut X)) {
+    if n == 0 {
+        return;
+    }
+    if incx == 1 {
+        for i in 0 .. n {
+            f(x.add(i));
+        }
+        return;
+    }
+    let mut px = first(x, n, incx);
+    for _ in 0 .. n {
+        f(px);
+        px = px.wrapping_offset(incx);
+    }
+}
+
+/// calls `f` with element `i` of `x` and of `y`, for `i` from 0 to `n - 1`
+///
+/// # Safety
+///
+/// Each vector must be valid for `(n - 1) * |inc| + 1` elements at its increment, for whatever accesses `f`
+/// makes.
+#[inline(always)]
+pub(crate) unsafe fn walk2<X, Y>(n: usize, x: *mut X, incx: isize, y: *mut Y, incy: isize,
+                                 mut f: impl FnMut(*mut X, *mut Y)) {
+    if n == 0 {
+        return;
+    }
+    if incx == 1 && incy == 1 {
+        for i in 0 .. n {
+            f(x.add(i), y.add(i));
+        }
+        return;
+    }
+    let (mut px, mut py) = (first(x, n, incx), first(y, n, incy));
+    for _ in 0 .. n {
+        f(px, py);
+        px = px.wrapping_offset(incx);
+        py = py.wrapping_offset(incy);
+    }
+}
+
+/// calls `f` with element `i` of `x`, `y` and `z`, for `i` from 0 to `n - 1`
+///
+/// # Safety
+///
+/// See `walk2`.
+#[inline(always)]
+pub(crate) unsafe fn walk3<X, Y, Z>(n: usize, x: *mut X, incx: isize, y: *mut Y, incy: isize, z: *mut Z,
+                                    incz: isize, mut f: impl FnMut(*mut X, *mut Y, *mut Z)) {
+    if n == 0 {
+        return;
+    }
+    if incx == 1 && incy == 1 && incz == 1 {
+        for i in 0 .. n {
+            f(x.add(i), y.add(i), z.add(i));
+        }
+        return;
+    }
+    let (mut px, mut py, mut pz) = (first(x, n, incx), first(y, n, incy), first(z, n, incz));
+    for _ in 0 .. n {
+        f(px, py, pz);
+        px = px.wrapping_offset(incx);
+        py = py.wrapping_offset(incy);
+        pz = pz.wrapping_offset(incz);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn walkers_visit_elements_in_blas_order() {
+        let mut x = [1, 2, 3, 4, 5];
+        let mut y = [10, 20, 30];
+        let mut z = [0; 3];
+        let mut seen = Vec::new();
+        // SAFETY: 3 elements at increment 2 span 5, at increment -1 and 1 they span 3.
+        unsafe {
+            walk1(3, x.as_mut_ptr(), -2, |px| seen.push(*px));
+            walk3(3, x.as_mut_ptr(), 2, y.as_mut_ptr(), -1, z.as_mut_ptr(), 1, |px, py, pz| *pz = *px + *py);
+        }
+        assert_eq!(seen, [5, 3, 1]);
+        assert_eq!(z, [31, 23, 15]);
+    }
+}
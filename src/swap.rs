@@ -1,54 +1,133 @@
-use crate::utils::{check_inc, get_first_index};
-
-fn swap<T>(n: isize, x: &mut [T], incx: isize, y: &mut [T], incy: isize) -> bool
-where T: Default + Copy,
-{
-    if check_inc(n, x, incx) != true || check_inc(n, y, incy) != true {
-        return false;
-    }
-
-    let n_usize = n as usize;
-    if incx == 1 && incy == 1 {
-        for i in 0 .. n_usize {
-            let temp = x[i];
-            x[i] = y[i];
-            y[i] = temp;
-        }
-        return true;
-    }
-
-    let incx_abs: usize = if incx > 0 {incx as usize} else {(-incx) as usize};
-    let mut ix: usize = get_first_index(n_usize, incx);
-    
-    let incy_abs: usize = if incy > 0 {incy as usize} else {(-incy) as usize};
-    let mut iy: usize = get_first_index(n_usize, incy);
-
-    for _ in 0 .. n_usize {
-        let temp = x[ix];
-        x[ix] = y[iy];
-        y[iy] = temp;
-
-        ix = if incx > 0 {
-            ix + incx_abs
-        } else {
-            ix - incx_abs
-        };
-        iy = if incy > 0 {
-            iy + incy_abs
-        } else {
-            iy - incy_abs
-        };
-    }
-
-    true
-}
-
-/// swaps two `f32` vectors, it interchanges n values of vector `x` and vector `y`
-pub fn sswap(n: isize, x: &mut [f32], incx: isize, y: &mut [f32], incy: isize) -> bool {
-    swap::<f32>(n, x, incx, y, incy)
-}
-
-/// swaps two `f64` vectors, it interchanges n values of vector `x` and vector `y`
-pub fn dswap(n: isize, x: &mut [f64], incx: isize, y: &mut [f64], incy: isize) -> bool {
-    swap::<f64>(n, x, incx, y, incy)
-}
\ No newline at end of file
+use std::ptr;
+use crate::error::BlasError;
+use crate::xerbla::report;
+use crate::trace::{self, Arg};
+use crate::view::{VecMut, Strided, check_len, check_within};
+use crate::utils::{check_n, check_inc};
+use crate::strided::walk2;
+use crate::dispatch;
+
+/// interchanges two vectors, for any element type
+pub fn swap<T>(n: isize, x: &mut [T], incx: isize, y: &mut [T], incy: isize) -> Result<(), BlasError> {
+    let n_usize = check_n(n, 1)?;
+    check_inc(n_usize, x, incx, 2, "x", "incx")?;
+    check_inc(n_usize, y, incy, 4, "y", "incy")?;
+
+    // SAFETY: both slices were checked to hold `n` elements at their increments, and cannot alias each other.
+    unsafe { swap_unchecked(n_usize, x.as_mut_ptr(), incx, y.as_mut_ptr(), incy) };
+    Ok(())
+}
+
+/// `swap` on raw pointers, validated by debug assertions only, like CBLAS `cblas_?swap`
+///
+/// As in BLAS, a negative increment walks the array from its end: element `i` of `x` is `x[(n - 1 - i) * |incx|]`.
+///
+/// # Safety
+///
+/// `x` must be valid for reads and writes of `(n - 1) * |incx| + 1` elements, `y` of `(n - 1) * |incy| + 1`
+/// elements, and no element may be reached through both `x` and `y`.
+pub unsafe fn swap_unchecked<T>(n: usize, x: *mut T, incx: isize, y: *mut T, incy: isize) {
+    debug_assert!(n == 0 || (!x.is_null() && !y.is_null()));
+    debug_assert!(n <= 1 || (incx != 0 && incy != 0), "increments must be nonzero");
+    if n == 0 {
+        return;
+    }
+
+    if incx == 1 && incy == 1 {
+        dispatch::swap_contiguous(n, x, y);
+        return;
+    }
+
+    walk2(n, x, incx, y, incy, |px, py| ptr::swap(px, py));
+}
+
+/// swaps two `f32` vectors, it interchanges n values of vector `x` and vector `y`
+pub fn sswap(n: isize, x: &mut [f32], incx: isize, y: &mut [f32], incy: isize) -> Result<(), BlasError> {
+    let call = trace::begin("sswap", &[
+        ("n", Arg::Int(n)), ("x", Arg::F32s(x)), ("incx", Arg::Int(incx)), ("y", Arg::F32s(y)),
+        ("incy", Arg::Int(incy)),
+    ]);
+    let result = swap::<f32>(n, x, incx, y, incy).map_err(|e| report("sswap", e));
+    trace::end(call, result.is_ok(), &[("x", Arg::F32s(x)), ("y", Arg::F32s(y))]);
+    result
+}
+
+/// swaps two `f64` vectors, it interchanges n values of vector `x` and vector `y`
+pub fn dswap(n: isize, x: &mut [f64], incx: isize, y: &mut [f64], incy: isize) -> Result<(), BlasError> {
+    let call = trace::begin("dswap", &[
+        ("n", Arg::Int(n)), ("x", Arg::F64s(x)), ("incx", Arg::Int(incx)), ("y", Arg::F64s(y)),
+        ("incy", Arg::Int(incy)),
+    ]);
+    let result = swap::<f64>(n, x, incx, y, incy).map_err(|e| report("dswap", e));
+    trace::end(call, result.is_ok(), &[("x", Arg::F64s(x)), ("y", Arg::F64s(y))]);
+    result
+}
+
+/// `sswap` on raw pointers without bounds checks
+///
+/// # Safety
+///
+/// See `swap_unchecked`.
+pub unsafe fn sswap_unchecked(n: usize, x: *mut f32, incx: isize, y: *mut f32, incy: isize) {
+    swap_unchecked::<f32>(n, x, incx, y, incy)
+}
+
+/// `dswap` on raw pointers without bounds checks
+///
+/// # Safety
+///
+/// See `swap_unchecked`.
+pub unsafe fn dswap_unchecked(n: usize, x: *mut f64, incx: isize, y: *mut f64, incy: isize) {
+    swap_unchecked::<f64>(n, x, incx, y, incy)
+}
+
+/// interchanges two vector views, for any element type
+pub fn swap_view<T>(x: &mut VecMut<'_, T>, y: &mut VecMut<'_, T>) -> Result<(), BlasError> {
+    check_len(x.len(), y.len(), 2, "y")?;
+    let (n, x, incx) = x.as_blas();
+    let (_, y, incy) = y.as_blas();
+    swap(n, x, incx, y, incy)
+}
+
+/// `sswap` on vector views
+pub fn sswap_view(x: &mut VecMut<'_, f32>, y: &mut VecMut<'_, f32>) -> Result<(), BlasError> {
+    swap_view::<f32>(x, y).map_err(|e| report("sswap_view", e))
+}
+
+/// `dswap` on vector views
+pub fn dswap_view(x: &mut VecMut<'_, f64>, y: &mut VecMut<'_, f64>) -> Result<(), BlasError> {
+    swap_view::<f64>(x, y).map_err(|e| report("dswap_view", e))
+}
+
+/// interchanges two non-overlapping vectors `x` and `y` stored in the same buffer
+pub(crate) fn swap_strided<T>(buf: &mut [T], x: Strided, y: Strided) {
+    debug_assert!(!x.overlaps(&y));
+    debug_assert!(x.bounds().1 <= buf.len() && y.bounds().1 <= buf.len());
+    let base = buf.as_mut_ptr();
+    // SAFETY: both vectors lie inside `buf` and do not share an element.
+    unsafe { swap_unchecked(x.len, base.add(x.bounds().0), x.stride, base.add(y.bounds().0), y.stride) };
+}
+
+/// interchanges two vectors of the same buffer, `x[i] = buf[offx + i * incx]` and `y[i] = buf[offy + i * incy]`,
+/// for any element type
+///
+/// The strides may be negative, and `x` and `y` must not share an element, otherwise `BlasError::Overlap` is
+/// returned.
+pub fn swap_within<T>(n: isize, buf: &mut [T], offx: usize, incx: isize, offy: usize, incy: isize) -> Result<(), BlasError> {
+    let (x, y) = check_within(n, buf.len(), offx, incx, offy, incy)?;
+    if x.overlaps(&y) {
+        return Err(BlasError::Overlap { pos: 5, name: "offy" });
+    }
+    swap_strided(buf, x, y);
+    Ok(())
+}
+
+/// `sswap` on two non-overlapping vectors of the same buffer, see `swap_within`
+pub fn sswap_within(n: isize, buf: &mut [f32], offx: usize, incx: isize, offy: usize, incy: isize) -> Result<(), BlasError> {
+    swap_within::<f32>(n, buf, offx, incx, offy, incy).map_err(|e| report("sswap_within", e))
+}
+
+/// `dswap` on two non-overlapping vectors of the same buffer, see `swap_within`
+pub fn dswap_within(n: isize, buf: &mut [f64], offx: usize, incx: isize, offy: usize, incy: isize) -> Result<(), BlasError> {
+    swap_within::<f64>(n, buf, offx, incx, offy, incy).map_err(|e| report("dswap_within", e))
+}
@@ -0,0 +1,209 @@
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::RwLock;
+use std::time::Instant;
+use crate::context::Context;
+use crate::record;
+#[cfg(feature = "sanitize")]
+use crate::sanitize;
+use crate::stats;
+
+/// Argument of a routine call, as seen by the tracing hooks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Arg<'a> {
+    Int(isize),
+    F32(f32),
+    F64(f64),
+    F32s(&'a [f32]),
+    F64s(&'a [f64]),
+}
+
+/// Receives one line per call while verbose mode is on.
+pub type VerboseHandler = fn(line: &str);
+
+/// Prints the line to standard error. This is the default handler.
+pub fn verbose_stderr(line: &str) {
+    eprintln!("{}", line);
+}
+
+const UNSET: u8 = 0;
+const OFF: u8 = 1;
+const ON: u8 = 2;
+
+static VERBOSE: AtomicU8 = AtomicU8::new(UNSET);
+static VERBOSE_HANDLER: RwLock<VerboseHandler> = RwLock::new(verbose_stderr);
+
+/// Whether verbose mode is on. Until `set_verbose` is called, it is on when the `RSBLAS_VERBOSE` environment
+/// variable is set to anything but `0` or an empty string.
+pub fn verbose() -> bool {
+    match VERBOSE.load(Ordering::Relaxed) {
+        UNSET => {
+            let on = std::env::var("RSBLAS_VERBOSE").map(|v| !v.is_empty() && v != "0").unwrap_or(false);
+            let state = if on { ON } else { OFF };
+            match VERBOSE.compare_exchange(UNSET, state, Ordering::Relaxed, Ordering::Relaxed) {
+                Ok(_) => on,
+                Err(current) => current == ON,
+            }
+        }
+        state => state == ON,
+    }
+}
+
+/// Turns verbose mode on or off, overriding `RSBLAS_VERBOSE`, and returns the previous setting.
+///
+/// In verbose mode every call of a typed BLAS routine (`saxpy`, `ddot`, ...) hands the verbose handler a line
+/// such as `RSBLAS_VERBOSE DAXPY(n=1000,alpha=2,incx=1,incy=1) 1.25us 1.60GFLOP/s`.
+pub fn set_verbose(on: bool) -> bool {
+    let prev = verbose();
+    VERBOSE.store(if on { ON } else { OFF }, Ordering::Relaxed);
+    prev
+}
+
+/// Installs the process-wide verbose handler and returns the previous one.
+pub fn set_verbose_handler(handler: VerboseHandler) -> VerboseHandler {
+    let mut global = VERBOSE_HANDLER.write().unwrap_or_else(|e| e.into_inner());
+    std::mem::replace(&mut *global, handler)
+}
+
+/// floating-point operations of a Level 1 routine, named without its type prefix, on `n` elements
+pub(crate) fn flops(routine: &str, n: usize) -> u64 {
+    let per_element = match routine {
+        "asum" | "scal" => 1,
+        "axpy" | "dot" | "nrm2" => 2,
+        "rot" => 6,
+        _ => 0,
+    };
+    per_element * n as u64
+}
+
+/// a call being traced, from `begin` to `end`
+pub(crate) struct Call {
+    routine: &'static str,
+    n: usize,
+    /// arguments formatted for the verbose line, `None` when verbose mode is off
+    verbose: Option<String>,
+    /// partly encoded trace record and whether it holds snapshots, `None` when not recording
+    record: Option<(Vec<u8>, bool)>,
+    /// whether every input was finite and the increment of each vector, to scan the outputs
+    #[cfg(feature = "sanitize")]
+    sanitize: (bool, Vec<(&'static str, isize)>),
+    start: Instant,
+}
+
+/// starts tracing a call of `routine`, `None` when no tracing is enabled
+#[inline]
+pub(crate) fn begin(routine: &'static str, args: &[(&'static str, Arg<'_>)]) -> Option<Call> {
+    if !cfg!(feature = "sanitize") && !verbose() && !record::recording() && !stats::stats_enabled() {
+        return None;
+    }
+    Some(begin_traced(routine, args))
+}
+
+#[cold]
+fn begin_traced(routine: &'static str, args: &[(&'static str, Arg<'_>)]) -> Call {
+    let n = args.iter().find_map(|&(name, arg)| match arg {
+        Arg::Int(value) if name == "n" => Some(value.max(0) as usize),
+        _ => None,
+    });
+    let verbose = if verbose() { Some(format_args(args)) } else { None };
+    let record = if record::recording() { record::begin(routine, args) } else { None };
+    Call {
+        routine,
+        n: n.unwrap_or(0),
+        verbose,
+        record,
+        #[cfg(feature = "sanitize")]
+        sanitize: (sanitize::check_inputs(routine, args), sanitize::increments(args)),
+        start: Instant::now(),
+    }
+}
+
+/// scalar arguments as `name=value` pairs, vectors left out
+fn format_args(args: &[(&'static str, Arg<'_>)]) -> String {
+    let mut line = String::new();
+    for &(name, arg) in args {
+        let sep = if line.is_empty() { "" } else { "," };
+        let _ = match arg {
+            Arg::Int(value) => write!(line, "{}{}={}", sep, name, value),
+            Arg::F32(value) => write!(line, "{}{}={}", sep, name, value),
+            Arg::F64(value) => write!(line, "{}{}={}", sep, name, value),
+            Arg::F32s(_) | Arg::F64s(_) => Ok(()),
+        };
+    }
+    line
+}
+
+/// finishes tracing a call started by `begin`, `outputs` being the vectors and values the routine wrote and `ok`
+/// whether it passed validation; a rejected call computed nothing, so it is neither checked nor counted
+#[inline]
+pub(crate) fn end(call: Option<Call>, ok: bool, outputs: &[(&'static str, Arg<'_>)]) {
+    if let Some(call) = call {
+        end_traced(call, ok, outputs);
+    }
+}
+
+#[cold]
+fn end_traced(call: Call, ok: bool, outputs: &[(&'static str, Arg<'_>)]) {
+    let elapsed = call.start.elapsed();
+    #[cfg(feature = "sanitize")]
+    if ok {
+        sanitize::check_outputs(call.routine, call.n, call.sanitize.0, outputs, &call.sanitize.1);
+    }
+    if let Some(args) = call.verbose {
+        let flops = flops(&call.routine[1 ..], call.n);
+        let mut line = format!("RSBLAS_VERBOSE {}({}) {:.2}us", call.routine.to_uppercase(), args,
+                               elapsed.as_secs_f64() * 1e6);
+        if ok && flops > 0 {
+            let _ = write!(line, " {:.2}GFLOP/s", flops as f64 / elapsed.as_secs_f64().max(1e-9) / 1e9);
+        }
+        let handler = *VERBOSE_HANDLER.read().unwrap_or_else(|e| e.into_inner());
+        handler(&line);
+    }
+    if let Some((buf, snapshots)) = call.record {
+        record::end(buf, snapshots, outputs);
+    }
+    if ok && stats::stats_enabled() {
+        let kind = &call.routine[1 ..];
+        let word = if call.routine.starts_with('s') { 4 } else { 8 };
+        let (flops, bytes) = (flops(kind, call.n), word * stats::words(kind, call.n));
+        stats::global_stats().add(call.routine, flops, bytes);
+        Context::current().stats().add(call.routine, flops, bytes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static LINES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn capture(line: &str) {
+        LINES.with(|l| l.borrow_mut().push(line.to_string()));
+    }
+
+    #[test]
+    fn verbose_mode_logs_each_call() {
+        let prev_handler = set_verbose_handler(capture);
+        let prev = set_verbose(true);
+        let mut y = [1.0, 2.0, 3.0];
+        crate::daxpy(3, 0.5, &[2.0, 2.0, 2.0], 1, &mut y, -1).unwrap();
+        let dot = crate::sdot(2, &[1.0, 2.0], 1, &[3.0, 4.0], 1).unwrap();
+        let prev_xerbla = crate::set_thread_xerbla(Some(crate::xerbla_silent));
+        assert!(crate::daxpy(4, 0.5, &[2.0, 2.0, 2.0], 1, &mut y, 1).is_err());
+        crate::set_thread_xerbla(prev_xerbla);
+        set_verbose(prev);
+        set_verbose_handler(prev_handler);
+
+        assert_eq!(dot, 11.0);
+        let lines = LINES.with(|l| l.take());
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("RSBLAS_VERBOSE DAXPY(n=3,alpha=0.5,incx=1,incy=-1) "));
+        assert!(lines[0].ends_with("GFLOP/s"));
+        assert!(lines[1].starts_with("RSBLAS_VERBOSE SDOT(n=2,incx=1,incy=1) "));
+        // a rejected call computed nothing, so it gets no rate
+        assert!(lines[2].starts_with("RSBLAS_VERBOSE DAXPY(n=4,") && !lines[2].ends_with("GFLOP/s"));
+    }
+}
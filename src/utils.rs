@@ -1,19 +1,53 @@
-pub fn check_inc<T>(n: isize, x: &[T], inc: isize) -> bool {
-    if n <= 0 {
-        return false;
-    }
-
-    if inc > 0 {
-        x.len() >= ((n as usize) - 1) * (inc as usize) + 1
-    } else {
-        x.len() >= ((n as usize) - 1) * ((-inc) as usize) + 1
-    }
-}
-
-pub fn get_first_index(n: usize, inc: isize) -> usize {
-    if inc > 0 {
-        0
-    } else {
-        (n - 1) * ((-inc) as usize)
-    }
-}
\ No newline at end of file
+use crate::error::BlasError;
+
+/// validates the dimension `n`, found at position `pos`, and converts it to `usize`
+pub fn check_n(n: isize, pos: usize) -> Result<usize, BlasError> {
+    if n < 0 {
+        return Err(BlasError::InvalidDimension { pos, name: "n", value: n });
+    }
+    Ok(n as usize)
+}
+
+/// number of elements a vector of `n` elements with increment `inc` spans, saturating on overflow
+pub fn required_len(n: usize, inc: isize) -> usize {
+    if n == 0 {
+        return 0;
+    }
+    (n - 1)
+        .checked_mul(inc.unsigned_abs())
+        .and_then(|span| span.checked_add(1))
+        .unwrap_or(usize::MAX)
+}
+
+/// validates a vector `x` at position `pos` followed by its nonzero increment `inc`
+pub fn check_inc<T>(n: usize, x: &[T], inc: isize, pos: usize, name: &'static str, inc_name: &'static str) -> Result<(), BlasError> {
+    if inc == 0 {
+        return Err(BlasError::InvalidIncrement { pos: pos + 1, name: inc_name, value: inc });
+    }
+    check_len(n, x, inc, pos, name)
+}
+
+/// validates a vector `x` at position `pos` followed by its positive increment `inc`
+pub fn check_inc_positive<T>(n: usize, x: &[T], inc: isize, pos: usize, name: &'static str, inc_name: &'static str) -> Result<(), BlasError> {
+    if inc <= 0 {
+        return Err(BlasError::InvalidIncrement { pos: pos + 1, name: inc_name, value: inc });
+    }
+    check_len(n, x, inc, pos, name)
+}
+
+/// validates that the vector `x` at position `pos` holds `n` elements at increment `inc`
+pub fn check_len<T>(n: usize, x: &[T], inc: isize, pos: usize, name: &'static str) -> Result<(), BlasError> {
+    let required = required_len(n, inc);
+    if x.len() < required {
+        return Err(BlasError::SliceTooShort { pos, name, required, actual: x.len() });
+    }
+    Ok(())
+}
+
+pub fn get_first_index(n: usize, inc: isize) -> usize {
+    if inc > 0 {
+        0
+    } else {
+        (n - 1) * inc.unsigned_abs()
+    }
+}
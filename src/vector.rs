@@ -0,0 +1,95 @@
+use std::ops::{Deref, DerefMut};
+use crate::aligned::AlignedBuf;
+use crate::scalar::Scalar;
+use crate::view::{VecRef, VecMut};
+
+/// Owned contiguous vector whose storage is aligned to `ALIGNMENT` bytes.
+///
+/// It dereferences to `[T]`, so it can be handed to `saxpy`, `sdot`, ... with `incx = 1`, or converted into a
+/// `VecRef`/`VecMut` for the view-based routines.
+#[derive(Debug)]
+pub struct Vector<T> {
+    data: AlignedBuf<T>,
+}
+
+impl<T: Scalar> Vector<T> {
+    pub fn zeros(len: usize) -> Self {
+        Vector::from_elem(T::zero(), len)
+    }
+
+    pub fn from_elem(value: T, len: usize) -> Self {
+        Vector { data: AlignedBuf::from_elem(value, len) }
+    }
+
+    /// Vector whose element `i` is `f(i)`.
+    pub fn from_fn<F: FnMut(usize) -> T>(len: usize, mut f: F) -> Self {
+        let mut v = Vector::zeros(len);
+        for (i, x) in v.iter_mut().enumerate() {
+            *x = f(i);
+        }
+        v
+    }
+
+    pub fn from_slice(values: &[T]) -> Self {
+        Vector { data: AlignedBuf::from_slice(values) }
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+
+    pub fn as_view(&self) -> VecRef<'_, T> {
+        VecRef::from_slice(&self.data)
+    }
+
+    pub fn as_view_mut(&mut self) -> VecMut<'_, T> {
+        VecMut::from_slice(&mut self.data)
+    }
+}
+
+impl<T: Copy> Clone for Vector<T> {
+    fn clone(&self) -> Self {
+        Vector { data: self.data.clone() }
+    }
+}
+
+impl<T> Deref for Vector<T> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        &self.data
+    }
+}
+
+impl<T> DerefMut for Vector<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+}
+
+impl<T: PartialEq> PartialEq for Vector<T> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Scalar> From<&[T]> for Vector<T> {
+    fn from(values: &[T]) -> Self {
+        Vector::from_slice(values)
+    }
+}
+
+impl<'a, T: Scalar> From<&'a Vector<T>> for VecRef<'a, T> {
+    fn from(v: &'a Vector<T>) -> Self {
+        v.as_view()
+    }
+}
+
+impl<'a, T: Scalar> From<&'a mut Vector<T>> for VecMut<'a, T> {
+    fn from(v: &'a mut Vector<T>) -> Self {
+        v.as_view_mut()
+    }
+}
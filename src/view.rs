@@ -0,0 +1,479 @@
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+use crate::error::BlasError;
+use crate::utils::{check_n, check_inc, get_first_index, required_len};
+
+/// position of `len` elements `offset + i * stride` inside a slice, validated once on construction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Strided {
+    pub offset: usize,
+    pub len: usize,
+    pub stride: isize,
+}
+
+impl Strided {
+    /// checks that every element lies inside a slice of `data_len` elements, arguments are numbered as in
+    /// `VecRef::new(data, offset, len, stride)`
+    pub fn new(data_len: usize, offset: usize, len: usize, stride: isize) -> Result<Self, BlasError> {
+        if len == 0 {
+            return Ok(Strided { offset: 0, len, stride: if stride == 0 { 1 } else { stride } });
+        }
+        if stride == 0 {
+            if len > 1 {
+                return Err(BlasError::InvalidIncrement { pos: 4, name: "stride", value: stride });
+            }
+            return Strided::new(data_len, offset, len, 1);
+        }
+
+        let span = required_len(len, stride);
+        if span == usize::MAX {
+            return Err(BlasError::SliceTooShort { pos: 1, name: "data", required: span, actual: data_len });
+        }
+        let (lowest, highest) = if stride > 0 {
+            (offset, offset.saturating_add(span - 1))
+        } else {
+            if offset < span - 1 {
+                return Err(BlasError::InvalidOffset { pos: 2, name: "offset", value: offset, required: span - 1 });
+            }
+            (offset - (span - 1), offset)
+        };
+        if highest >= data_len {
+            return Err(BlasError::SliceTooShort { pos: 1, name: "data", required: highest.saturating_add(1), actual: data_len });
+        }
+        debug_assert!(lowest <= highest);
+        Ok(Strided { offset, len, stride })
+    }
+
+    pub fn index(&self, i: usize) -> usize {
+        (self.offset as isize + (i as isize) * self.stride) as usize
+    }
+
+    /// index of the lowest and one past the highest element in the underlying slice
+    pub fn bounds(&self) -> (usize, usize) {
+        if self.len == 0 {
+            return (0, 0);
+        }
+        let last = self.index(self.len - 1);
+        if self.stride > 0 { (self.offset, last + 1) } else { (last, self.offset + 1) }
+    }
+
+    pub fn sub(&self, start: usize, len: usize) -> Option<Self> {
+        if start.checked_add(len)? > self.len {
+            return None;
+        }
+        let offset = if len == 0 { 0 } else { self.index(start) };
+        Some(Strided { offset, len, stride: self.stride })
+    }
+
+    pub fn rev(&self) -> Self {
+        if self.len <= 1 {
+            // nothing to reverse, and a unit stride keeps `isize::MIN` from being negated
+            return Strided { stride: 1, ..*self };
+        }
+        Strided { offset: self.index(self.len - 1), len: self.len, stride: -self.stride }
+    }
+
+    /// same elements walked with a positive stride, for routines whose result does not depend on the order
+    pub fn forward(&self) -> Self {
+        if self.stride < 0 { self.rev() } else { *self }
+    }
+
+    /// whether the two layouts share at least one element of the underlying slice
+    pub fn overlaps(&self, other: &Strided) -> bool {
+        let (lo, hi) = self.bounds();
+        let (other_lo, other_hi) = other.bounds();
+        if self.len == 0 || other.len == 0 || hi <= other_lo || other_hi <= lo {
+            return false;
+        }
+        let step = self.stride.unsigned_abs();
+        let other_step = other.stride.unsigned_abs();
+        if step == other_step {
+            // two progressions with the same step and intersecting ranges meet iff they are in phase
+            return lo % step == other_lo % step;
+        }
+        (0 .. self.len).map(|i| self.index(i)).any(|i| {
+            i >= other_lo && i < other_hi && (i - other_lo) % other_step == 0
+        })
+    }
+}
+
+/// validates two vectors `x` and `y` of `n` elements inside one buffer, arguments numbered as in
+/// `srot_within(n, buf, offx, incx, offy, incy, ..)`
+pub(crate) fn check_within(n: isize, buf_len: usize, offx: usize, incx: isize, offy: usize, incy: isize) -> Result<(Strided, Strided), BlasError> {
+    let n_usize = check_n(n, 1)?;
+    let x = Strided::new(buf_len, offx, n_usize, incx).map_err(|e| renumber(e, 3, "offx", "incx"))?;
+    let y = Strided::new(buf_len, offy, n_usize, incy).map_err(|e| renumber(e, 5, "offy", "incy"))?;
+    Ok((x, y))
+}
+
+/// moves an error of `Strided::new` onto the `(off, inc)` pair at position `pos` of a `_within` routine
+fn renumber(err: BlasError, pos: usize, off_name: &'static str, inc_name: &'static str) -> BlasError {
+    match err {
+        BlasError::SliceTooShort { required, actual, .. } => BlasError::SliceTooShort { pos: 2, name: "buf", required, actual },
+        BlasError::InvalidOffset { value, required, .. } => BlasError::InvalidOffset { pos, name: off_name, value, required },
+        BlasError::InvalidIncrement { value, .. } => BlasError::InvalidIncrement { pos: pos + 1, name: inc_name, value },
+        err => err,
+    }
+}
+
+/// Immutable view of `len` elements `data[offset + i * stride]`, where `stride` may be negative.
+///
+/// The view is checked to stay inside `data` when it is built, so the Level 1 routines taking views
+/// (`sdot_view`, `saxpy_view`, ...) only need to compare lengths.
+#[derive(Debug, Clone, Copy)]
+pub struct VecRef<'a, T> {
+    data: &'a [T],
+    layout: Strided,
+}
+
+impl<'a, T> VecRef<'a, T> {
+    /// View of `len` elements `data[offset + i * stride]`. A zero `stride` is only accepted for `len <= 1`.
+    pub fn new(data: &'a [T], offset: usize, len: usize, stride: isize) -> Result<Self, BlasError> {
+        Ok(VecRef { data, layout: Strided::new(data.len(), offset, len, stride)? })
+    }
+
+    /// Contiguous view of the whole slice.
+    pub fn from_slice(data: &'a [T]) -> Self {
+        VecRef { data, layout: Strided { offset: 0, len: data.len(), stride: 1 } }
+    }
+
+    /// View of the BLAS vector argument `(n, x, incx)`, a negative `incx` starts from the end of `x`.
+    pub fn from_blas(n: isize, x: &'a [T], incx: isize) -> Result<Self, BlasError> {
+        let n_usize = check_n(n, 1)?;
+        check_inc(n_usize, x, incx, 2, "x", "incx")?;
+        let offset = if n_usize == 0 { 0 } else { get_first_index(n_usize, incx) };
+        Ok(VecRef { data: x, layout: Strided { offset, len: n_usize, stride: incx } })
+    }
+
+    pub(crate) fn from_parts(data: &'a [T], layout: Strided) -> Self {
+        VecRef { data, layout }
+    }
+
+    pub fn len(&self) -> usize {
+        self.layout.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layout.len == 0
+    }
+
+    /// Index in the underlying slice of the first element.
+    pub fn offset(&self) -> usize {
+        self.layout.offset
+    }
+
+    pub fn stride(&self) -> isize {
+        self.layout.stride
+    }
+
+    pub fn get(&self, i: usize) -> Option<&'a T> {
+        if i < self.layout.len { Some(&self.data[self.layout.index(i)]) } else { None }
+    }
+
+    pub fn iter(&self) -> Iter<'a, T> {
+        Iter { data: self.data, layout: self.layout, front: 0, back: self.layout.len }
+    }
+
+    /// View of the `len` elements starting at element `start`, `None` if it does not fit.
+    pub fn subview(&self, start: usize, len: usize) -> Option<Self> {
+        Some(VecRef { data: self.data, layout: self.layout.sub(start, len)? })
+    }
+
+    /// Same elements in reverse order.
+    pub fn rev(self) -> Self {
+        VecRef { data: self.data, layout: self.layout.rev() }
+    }
+
+    /// The elements as a plain slice when the stride is 1.
+    pub fn as_slice(&self) -> Option<&'a [T]> {
+        if self.layout.stride != 1 {
+            return None;
+        }
+        let (lo, hi) = self.layout.bounds();
+        Some(&self.data[lo .. hi])
+    }
+
+    /// lowers the view to the BLAS triple `(n, x, incx)`
+    pub(crate) fn as_blas(&self) -> (isize, &'a [T], isize) {
+        let (lo, hi) = self.layout.bounds();
+        (self.layout.len as isize, &self.data[lo .. hi], self.layout.stride)
+    }
+
+    /// same elements with a positive stride, see `Strided::forward`
+    pub(crate) fn forward(self) -> Self {
+        VecRef { data: self.data, layout: self.layout.forward() }
+    }
+}
+
+impl<'a, T> From<&'a [T]> for VecRef<'a, T> {
+    fn from(data: &'a [T]) -> Self {
+        VecRef::from_slice(data)
+    }
+}
+
+impl<'a, T> Index<usize> for VecRef<'a, T> {
+    type Output = T;
+    fn index(&self, i: usize) -> &T {
+        assert!(i < self.layout.len, "index {} out of range for view of length {}", i, self.layout.len);
+        &self.data[self.layout.index(i)]
+    }
+}
+
+impl<'a, T> IntoIterator for VecRef<'a, T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+/// Mutable view of `len` elements `data[offset + i * stride]`, where `stride` may be negative.
+#[derive(Debug)]
+pub struct VecMut<'a, T> {
+    data: &'a mut [T],
+    layout: Strided,
+}
+
+impl<'a, T> VecMut<'a, T> {
+    /// View of `len` elements `data[offset + i * stride]`. A zero `stride` is only accepted for `len <= 1`.
+    pub fn new(data: &'a mut [T], offset: usize, len: usize, stride: isize) -> Result<Self, BlasError> {
+        let layout = Strided::new(data.len(), offset, len, stride)?;
+        Ok(VecMut { data, layout })
+    }
+
+    /// Contiguous view of the whole slice.
+    pub fn from_slice(data: &'a mut [T]) -> Self {
+        let layout = Strided { offset: 0, len: data.len(), stride: 1 };
+        VecMut { data, layout }
+    }
+
+    /// View of the BLAS vector argument `(n, x, incx)`, a negative `incx` starts from the end of `x`.
+    pub fn from_blas(n: isize, x: &'a mut [T], incx: isize) -> Result<Self, BlasError> {
+        let n_usize = check_n(n, 1)?;
+        check_inc(n_usize, x, incx, 2, "x", "incx")?;
+        let offset = if n_usize == 0 { 0 } else { get_first_index(n_usize, incx) };
+        Ok(VecMut { data: x, layout: Strided { offset, len: n_usize, stride: incx } })
+    }
+
+    pub(crate) fn from_parts(data: &'a mut [T], layout: Strided) -> Self {
+        VecMut { data, layout }
+    }
+
+    pub fn len(&self) -> usize {
+        self.layout.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layout.len == 0
+    }
+
+    /// Index in the underlying slice of the first element.
+    pub fn offset(&self) -> usize {
+        self.layout.offset
+    }
+
+    pub fn stride(&self) -> isize {
+        self.layout.stride
+    }
+
+    pub fn get(&self, i: usize) -> Option<&T> {
+        if i < self.layout.len { Some(&self.data[self.layout.index(i)]) } else { None }
+    }
+
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        if i < self.layout.len { Some(&mut self.data[self.layout.index(i)]) } else { None }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { data: self.data, layout: self.layout, front: 0, back: self.layout.len }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut { ptr: self.data.as_mut_ptr(), layout: self.layout, front: 0, back: self.layout.len, marker: PhantomData }
+    }
+
+    /// Immutable view of the same elements.
+    pub fn as_ref(&self) -> VecRef<'_, T> {
+        VecRef { data: self.data, layout: self.layout }
+    }
+
+    /// Reborrows the view, so it can be passed by value and used again afterwards.
+    pub fn rb_mut(&mut self) -> VecMut<'_, T> {
+        VecMut { data: self.data, layout: self.layout }
+    }
+
+    /// View of the `len` elements starting at element `start`, `None` if it does not fit.
+    pub fn subview(&self, start: usize, len: usize) -> Option<VecRef<'_, T>> {
+        Some(VecRef { data: self.data, layout: self.layout.sub(start, len)? })
+    }
+
+    /// Mutable view of the `len` elements starting at element `start`, `None` if it does not fit.
+    pub fn subview_mut(&mut self, start: usize, len: usize) -> Option<VecMut<'_, T>> {
+        Some(VecMut { layout: self.layout.sub(start, len)?, data: self.data })
+    }
+
+    /// Same elements in reverse order.
+    pub fn rev(self) -> Self {
+        VecMut { layout: self.layout.rev(), data: self.data }
+    }
+
+    /// The elements as a plain slice when the stride is 1.
+    pub fn as_slice_mut(&mut self) -> Option<&mut [T]> {
+        if self.layout.stride != 1 {
+            return None;
+        }
+        let (lo, hi) = self.layout.bounds();
+        Some(&mut self.data[lo .. hi])
+    }
+
+    /// lowers the view to the BLAS triple `(n, x, incx)`
+    pub(crate) fn as_blas(&mut self) -> (isize, &mut [T], isize) {
+        let (lo, hi) = self.layout.bounds();
+        (self.layout.len as isize, &mut self.data[lo .. hi], self.layout.stride)
+    }
+
+    /// same elements with a positive stride, see `Strided::forward`
+    pub(crate) fn forward(&mut self) -> VecMut<'_, T> {
+        VecMut { layout: self.layout.forward(), data: self.data }
+    }
+}
+
+impl<'a, T> From<&'a mut [T]> for VecMut<'a, T> {
+    fn from(data: &'a mut [T]) -> Self {
+        VecMut::from_slice(data)
+    }
+}
+
+impl<'a, T> Index<usize> for VecMut<'a, T> {
+    type Output = T;
+    fn index(&self, i: usize) -> &T {
+        assert!(i < self.layout.len, "index {} out of range for view of length {}", i, self.layout.len);
+        &self.data[self.layout.index(i)]
+    }
+}
+
+impl<'a, T> IndexMut<usize> for VecMut<'a, T> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        assert!(i < self.layout.len, "index {} out of range for view of length {}", i, self.layout.len);
+        &mut self.data[self.layout.index(i)]
+    }
+}
+
+/// Iterator over the elements of a `VecRef` or `VecMut`.
+#[derive(Debug, Clone)]
+pub struct Iter<'a, T> {
+    data: &'a [T],
+    layout: Strided,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        if self.front == self.back {
+            return None;
+        }
+        let item = &self.data[self.layout.index(self.front)];
+        self.front += 1;
+        Some(item)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.back - self.front, Some(self.back - self.front))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(&self.data[self.layout.index(self.back)])
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+
+/// Mutable iterator over the elements of a `VecMut`.
+#[derive(Debug)]
+pub struct IterMut<'a, T> {
+    ptr: *mut T,
+    layout: Strided,
+    front: usize,
+    back: usize,
+    marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.front == self.back {
+            return None;
+        }
+        let i = self.layout.index(self.front);
+        self.front += 1;
+        // SAFETY: the layout was validated against the slice, and distinct positions map to distinct
+        // elements because the stride is nonzero, so every reference handed out is unique.
+        Some(unsafe { &mut *self.ptr.add(i) })
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.back - self.front, Some(self.back - self.front))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        let i = self.layout.index(self.back);
+        // SAFETY: see `next`.
+        Some(unsafe { &mut *self.ptr.add(i) })
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+impl<'a, T> FusedIterator for IterMut<'a, T> {}
+
+/// checks that `actual`, the length of argument `pos`, matches `expected`
+pub(crate) fn check_len(expected: usize, actual: usize, pos: usize, name: &'static str) -> Result<(), BlasError> {
+    if expected != actual {
+        return Err(BlasError::DimensionMismatch { pos, name, expected, actual });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negative_stride_walks_backwards() {
+        let data = [0, 1, 2, 3, 4, 5, 6];
+        let v = VecRef::new(&data, 6, 3, -2).unwrap();
+        assert_eq!(v.iter().copied().collect::<Vec<_>>(), [6, 4, 2]);
+        assert_eq!(v.rev().iter().copied().collect::<Vec<_>>(), [2, 4, 6]);
+        assert_eq!(v.subview(1, 2).unwrap().iter().copied().collect::<Vec<_>>(), [4, 2]);
+        assert_eq!(v.as_blas(), (3, &data[2 .. 7], -2));
+
+        let b = VecRef::from_blas(3, &data, -2).unwrap();
+        assert_eq!(b.iter().copied().collect::<Vec<_>>(), [4, 2, 0]);
+
+        assert_eq!(VecRef::new(&data, 3, 3, -2).unwrap_err(), BlasError::InvalidOffset { pos: 2, name: "offset", value: 3, required: 4 });
+        assert_eq!(VecRef::new(&data, 1, 4, 2).unwrap_err(), BlasError::SliceTooShort { pos: 1, name: "data", required: 8, actual: 7 });
+    }
+
+    #[test]
+    fn iter_mut_visits_each_element_once() {
+        let mut data = [0; 6];
+        let mut v = VecMut::new(&mut data, 1, 3, 2).unwrap();
+        for (i, x) in v.iter_mut().rev().enumerate() {
+            *x = i + 1;
+        }
+        assert_eq!(data, [0, 3, 0, 2, 0, 1]);
+    }
+}
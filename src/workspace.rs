@@ -0,0 +1,263 @@
+use std::alloc::{self, Layout};
+use std::fmt;
+use std::mem;
+use std::ptr::NonNull;
+use std::slice;
+use std::sync::{Arc, Mutex, MutexGuard};
+use crate::aligned::{AlignedBuf, ALIGNMENT};
+
+/// Source of the memory a `ScratchPool` hands out.
+///
+/// Real-time code can supply its own allocator, or an `Arena` carved out of memory set aside up front, so that
+/// the pool never touches the global heap.
+pub trait ScratchAllocator: Send + Sync {
+    /// Allocates `bytes` bytes, `bytes > 0`, aligned to `ALIGNMENT` bytes, or returns `None` when out of memory.
+    fn allocate(&self, bytes: usize) -> Option<NonNull<u8>>;
+
+    /// Gives back memory obtained from `allocate`.
+    ///
+    /// # Safety
+    /// `ptr` must have been returned by `allocate` of this allocator with the same `bytes`, and not be used
+    /// afterwards.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, bytes: usize);
+}
+
+/// Global heap allocator. This is the default allocator of a pool.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HeapAllocator;
+
+impl ScratchAllocator for HeapAllocator {
+    fn allocate(&self, bytes: usize) -> Option<NonNull<u8>> {
+        let layout = Layout::from_size_align(bytes, ALIGNMENT).ok()?;
+        // SAFETY: `bytes` is not zero.
+        NonNull::new(unsafe { alloc::alloc(layout) })
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, bytes: usize) {
+        alloc::dealloc(ptr.as_ptr(), Layout::from_size_align_unchecked(bytes, ALIGNMENT));
+    }
+}
+
+/// Fixed block of memory allocated once, handed out front to back.
+///
+/// The space of freed allocations is reclaimed when none is left in use, which suits a `ScratchPool` since the
+/// pool keeps its buffers until it is cleared.
+pub struct Arena {
+    _buf: AlignedBuf<u8>,
+    base: NonNull<u8>,
+    capacity: usize,
+    /// offset of the first free byte and number of allocations in use
+    state: Mutex<(usize, usize)>,
+}
+
+// SAFETY: the arena owns its block, and `state` serialises the handing out of its parts.
+unsafe impl Send for Arena {}
+unsafe impl Sync for Arena {}
+
+impl Arena {
+    /// Arena of `capacity` bytes, allocated now.
+    pub fn new(capacity: usize) -> Self {
+        let mut buf = AlignedBuf::from_elem(0_u8, capacity);
+        let base = NonNull::new(buf.as_mut_ptr()).unwrap_or(NonNull::dangling());
+        Arena { _buf: buf, base, capacity, state: Mutex::new((0, 0)) }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Bytes handed out and not yet reclaimed.
+    pub fn used(&self) -> usize {
+        self.lock().0
+    }
+
+    fn lock(&self) -> MutexGuard<'_, (usize, usize)> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl ScratchAllocator for Arena {
+    fn allocate(&self, bytes: usize) -> Option<NonNull<u8>> {
+        let mut state = self.lock();
+        if bytes > self.capacity - state.0 {
+            return None;
+        }
+        // SAFETY: `state.0 + bytes` is within the block.
+        let ptr = unsafe { self.base.as_ptr().add(state.0) };
+        // the next allocation starts on the following `ALIGNMENT` boundary, or at the end of the block
+        let end = (state.0 + bytes).checked_add(ALIGNMENT - 1).map_or(self.capacity, |e| e / ALIGNMENT * ALIGNMENT);
+        *state = (end.min(self.capacity), state.1 + 1);
+        NonNull::new(ptr)
+    }
+
+    unsafe fn deallocate(&self, _ptr: NonNull<u8>, _bytes: usize) {
+        let mut state = self.lock();
+        state.1 -= 1;
+        if state.1 == 0 {
+            state.0 = 0;
+        }
+    }
+}
+
+impl fmt::Debug for Arena {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Arena").field("capacity", &self.capacity).field("used", &self.used()).finish()
+    }
+}
+
+/// scratch buffer owned by a pool, given back to its allocator on drop
+struct Block {
+    ptr: NonNull<u8>,
+    bytes: usize,
+    allocator: Arc<dyn ScratchAllocator>,
+}
+
+// SAFETY: the block owns its memory, which is only reached through a borrow of the block.
+unsafe impl Send for Block {}
+
+impl Drop for Block {
+    fn drop(&mut self) {
+        // SAFETY: `ptr` came from `allocator` with `bytes` and the block is its only owner.
+        unsafe { self.allocator.deallocate(self.ptr, self.bytes) };
+    }
+}
+
+/// Cache of aligned byte buffers that routines borrow for temporaries instead of allocating on every call.
+///
+/// A buffer is taken out of the pool for the duration of a borrow, so a pool may be shared between threads and
+/// routines that call each other; it is handed back afterwards for the next call to reuse. Buffers come from
+/// the pool's `ScratchAllocator`; `reserve` with the size returned by a workspace query such as
+/// `copy_within_workspace` makes sure the calls that follow allocate nothing.
+pub struct ScratchPool {
+    allocator: Arc<dyn ScratchAllocator>,
+    buffers: Mutex<Vec<Block>>,
+}
+
+impl Default for ScratchPool {
+    fn default() -> Self {
+        ScratchPool::with_allocator(Arc::new(HeapAllocator))
+    }
+}
+
+impl ScratchPool {
+    pub fn new() -> Self {
+        ScratchPool::default()
+    }
+
+    /// Pool taking its buffers from `allocator`.
+    pub fn with_allocator(allocator: Arc<dyn ScratchAllocator>) -> Self {
+        ScratchPool { allocator, buffers: Mutex::new(Vec::new()) }
+    }
+
+    /// Total size, in bytes, of the buffers currently kept for reuse.
+    pub fn retained_bytes(&self) -> usize {
+        self.lock().iter().map(|b| b.bytes).sum()
+    }
+
+    /// Releases every buffer kept for reuse.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Makes sure a buffer of at least `bytes` bytes is kept for reuse, allocating it now if needed. Returns
+    /// `false` when the allocator is out of memory.
+    pub fn reserve(&self, bytes: usize) -> bool {
+        if bytes == 0 || self.lock().iter().any(|b| b.bytes >= bytes) {
+            return true;
+        }
+        match self.allocate(bytes) {
+            Some(block) => {
+                self.lock().push(block);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Calls `f` with `len` elements of scratch memory set to `value`, aligned to `ALIGNMENT` bytes.
+    ///
+    /// # Panics
+    /// When the allocator is out of memory.
+    pub fn with_scratch<T: Copy, R>(&self, len: usize, value: T, f: impl FnOnce(&mut [T]) -> R) -> R {
+        assert!(mem::align_of::<T>() <= ALIGNMENT, "scratch element alignment exceeds ALIGNMENT");
+        let bytes = mem::size_of::<T>().checked_mul(len).expect("capacity overflow");
+        if bytes == 0 {
+            let mut empty = vec![value; len];
+            return f(&mut empty);
+        }
+        let block = self.take(bytes);
+        // SAFETY: `block` holds at least `bytes` bytes, starts on an `ALIGNMENT` boundary, which satisfies the
+        // alignment of `T`, and is exclusively ours until it is given back below.
+        let scratch = unsafe { slice::from_raw_parts_mut(block.ptr.as_ptr() as *mut T, len) };
+        for x in scratch.iter_mut() {
+            *x = value;
+        }
+        let result = f(scratch);
+        self.lock().push(block);
+        result
+    }
+
+    /// smallest cached buffer of at least `bytes` bytes, or a fresh one
+    fn take(&self, bytes: usize) -> Block {
+        let cached = {
+            let mut buffers = self.lock();
+            let best = buffers.iter().enumerate()
+                .filter(|(_, b)| b.bytes >= bytes)
+                .min_by_key(|(_, b)| b.bytes)
+                .map(|(i, _)| i);
+            best.map(|i| buffers.swap_remove(i))
+        };
+        cached.or_else(|| self.allocate(bytes)).expect("scratch allocator out of memory")
+    }
+
+    fn allocate(&self, bytes: usize) -> Option<Block> {
+        let ptr = self.allocator.allocate(bytes)?;
+        debug_assert_eq!(ptr.as_ptr() as usize % ALIGNMENT, 0, "scratch allocator broke the alignment");
+        Some(Block { ptr, bytes, allocator: self.allocator.clone() })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Block>> {
+        self.buffers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl fmt::Debug for ScratchPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScratchPool").field("retained_bytes", &self.retained_bytes()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scratch_buffers_are_reused() {
+        let pool = ScratchPool::new();
+        let sum = pool.with_scratch(16, 1.0_f64, |s| {
+            assert_eq!(s.as_ptr() as usize % ALIGNMENT, 0);
+            s.iter().sum::<f64>()
+        });
+        assert_eq!(sum, 16.0);
+        assert_eq!(pool.retained_bytes(), 128);
+        pool.with_scratch(4, 0_u32, |s| assert_eq!(s, [0; 4]));
+        assert_eq!(pool.retained_bytes(), 128);
+        pool.clear();
+        assert_eq!(pool.retained_bytes(), 0);
+    }
+
+    #[test]
+    fn arena_backed_pool_allocates_nothing_after_reserve() {
+        let arena = Arc::new(Arena::new(256));
+        let pool = ScratchPool::with_allocator(arena.clone());
+        assert!(pool.reserve(100));
+        assert_eq!(arena.used(), 128);
+        pool.with_scratch(25, 1.0_f32, |s| assert_eq!(s.len(), 25));
+        pool.with_scratch(10, 2.0_f64, |s| assert_eq!(s[9], 2.0));
+        assert_eq!(arena.used(), 128);
+        assert!(!pool.reserve(200));
+        pool.clear();
+        assert_eq!(arena.used(), 0);
+        assert!(pool.reserve(200));
+    }
+}
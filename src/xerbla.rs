@@ -0,0 +1,54 @@
+use std::cell::Cell;
+use std::sync::RwLock;
+use crate::error::BlasError;
+
+/// Error handler called with the routine name and the rejected argument, modelled on reference BLAS `XERBLA`.
+///
+/// The handler runs before the routine returns its `Err`, so returning from it keeps the silent behaviour while
+/// panicking aborts the call.
+pub type XerblaHandler = fn(routine: &str, err: &BlasError);
+
+/// Ignores the error, the routine just returns its error value. This is the default handler.
+pub fn xerbla_silent(_routine: &str, _err: &BlasError) {}
+
+/// Prints the routine name and the rejected argument to standard error, then lets the routine return.
+pub fn xerbla_log(routine: &str, err: &BlasError) {
+    eprintln!(" ** On entry to {} {}", routine.to_uppercase(), err);
+}
+
+/// Panics with the routine name and the argument index.
+pub fn xerbla_panic(routine: &str, err: &BlasError) {
+    panic!("On entry to {} {}", routine.to_uppercase(), err);
+}
+
+static GLOBAL_HANDLER: RwLock<XerblaHandler> = RwLock::new(xerbla_silent);
+
+thread_local! {
+    static THREAD_HANDLER: Cell<Option<XerblaHandler>> = const { Cell::new(None) };
+}
+
+/// Installs the process-wide error handler and returns the previous one.
+pub fn set_xerbla(handler: XerblaHandler) -> XerblaHandler {
+    let mut global = GLOBAL_HANDLER.write().unwrap_or_else(|e| e.into_inner());
+    std::mem::replace(&mut *global, handler)
+}
+
+/// Overrides the error handler for the calling thread only, `None` falls back to the process-wide handler.
+/// Returns the previous override.
+pub fn set_thread_xerbla(handler: Option<XerblaHandler>) -> Option<XerblaHandler> {
+    THREAD_HANDLER.with(|h| h.replace(handler))
+}
+
+/// Reports `err`, raised by `routine`, to the error handler in effect on the calling thread.
+pub fn xerbla(routine: &str, err: &BlasError) {
+    let handler = THREAD_HANDLER.with(|h| h.get()).unwrap_or_else(|| {
+        *GLOBAL_HANDLER.read().unwrap_or_else(|e| e.into_inner())
+    });
+    handler(routine, err);
+}
+
+/// reports `err` through `xerbla` and hands it back, for use in `map_err`
+pub(crate) fn report(routine: &'static str, err: BlasError) -> BlasError {
+    xerbla(routine, &err);
+    err
+}